@@ -0,0 +1,231 @@
+//! Grids drawn frame by frame on a terminal, for watching a simulation unfold. Every frame clears the screen
+//! and draws the grid from the top left, with the colours of each value picked by a closure.
+
+use std::{
+    io::{self, BufRead, Write},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::Duration,
+};
+
+pub use ansi_control_codes::control_sequences::GraphicRendition;
+use ansi_control_codes::control_sequences::{ErasePage, CUP, ED, SGR};
+
+use crate::Grid;
+
+/// How one value of a grid is drawn: a symbol in some renditions, the default rendition when empty.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Cell {
+    pub symbol: char,
+    pub rendition: &'static [GraphicRendition],
+}
+
+impl Cell {
+    pub fn new(symbol: char, rendition: &'static [GraphicRendition]) -> Cell {
+        Cell { symbol, rendition }
+    }
+
+    pub fn plain(symbol: char) -> Cell {
+        Cell::new(symbol, &[])
+    }
+}
+
+/// The `grid` as text, one line per row, with `cell` deciding how each value is drawn. Renditions are only
+/// switched where they change.
+pub fn render<T, F>(grid: &Grid<T>, cell: F) -> String
+where
+    F: Fn(&T) -> Cell,
+{
+    let mut result = String::new();
+
+    for row in grid.rows() {
+        let mut current: Option<&[GraphicRendition]> = None;
+
+        for value in row {
+            let Cell { symbol, rendition } = cell(value);
+
+            if current != Some(rendition) {
+                result += &SGR(None).to_string();
+                if !rendition.is_empty() {
+                    result += &SGR(Some(rendition.to_vec())).to_string();
+                }
+                current = Some(rendition);
+            }
+            result.push(symbol);
+        }
+        result += &format!("{}\n", SGR(None));
+    }
+    result
+}
+
+/// Draws frames to `out` with a `delay` in between. Viewers steer it by sending lines on the `controls`:
+/// while playing any line pauses; while paused an empty line shows the next frame and `p` plays again.
+/// `q` quits either way.
+pub struct Animation<W> {
+    out: W,
+    delay: Duration,
+    paused: bool,
+    controls: Option<Receiver<String>>,
+}
+
+impl Animation<io::Stdout> {
+    /// Draws on standard output with the lines typed on standard input as controls.
+    pub fn terminal() -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        // Reading blocks, so a thread of its own forwards the lines as they come
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Animation::new(io::stdout()).controls(receiver)
+    }
+}
+
+impl<W: Write> Animation<W> {
+    /// Plays 10 frames a second, without controls.
+    pub fn new(out: W) -> Self {
+        Animation {
+            out,
+            delay: Duration::from_millis(100),
+            paused: false,
+            controls: None,
+        }
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Start paused, waiting for a control after the first frame. Without controls this does nothing.
+    pub fn paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self
+    }
+
+    pub fn controls(mut self, controls: Receiver<String>) -> Self {
+        self.controls = Some(controls);
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    /// Draw `grid` under a `caption`, then wait for the delay or the viewer. Returns whether to go on
+    /// with the next frame, `false` once the viewer quit.
+    pub fn frame<T, F>(&mut self, caption: &str, grid: &Grid<T>, cell: F) -> io::Result<bool>
+    where
+        F: Fn(&T) -> Cell,
+    {
+        write!(
+            self.out,
+            "{}{}{}\n{}",
+            ED(Some(ErasePage::BeginToEnd)),
+            CUP(None, None),
+            caption,
+            render(grid, cell)
+        )?;
+        if self.controls.is_some() {
+            let help = if self.paused {
+                "Enter: next frame, p Enter: play, q Enter: quit"
+            } else {
+                "Enter: pause, q Enter: quit"
+            };
+            writeln!(self.out, "{}", help)?;
+        }
+        self.out.flush()?;
+
+        Ok(self.wait())
+    }
+
+    fn wait(&mut self) -> bool {
+        let Some(controls) = &self.controls else {
+            thread::sleep(self.delay);
+            return true;
+        };
+
+        if self.paused {
+            match controls.recv() {
+                Ok(line) => match line.trim() {
+                    "q" => return false,
+                    "p" => self.paused = false,
+                    _ => {}
+                },
+                // Nobody left to step, play the rest
+                Err(_) => self.paused = false,
+            }
+        } else {
+            thread::sleep(self.delay);
+
+            loop {
+                match controls.try_recv() {
+                    Ok(line) if line.trim() == "q" => return false,
+                    Ok(_) => self.paused = true,
+                    Err(TryRecvError::Empty | TryRecvError::Disconnected) => break,
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, time::Duration};
+
+    use super::{render, Animation, Cell, GraphicRendition};
+    use crate::Grid;
+
+    const RED: &[GraphicRendition] = &[GraphicRendition::RedForeground];
+
+    fn cell(value: &u32) -> Cell {
+        match value {
+            0 => Cell::plain('.'),
+            _ => Cell::new(char::from_digit(*value, 10).unwrap(), RED),
+        }
+    }
+
+    #[test]
+    fn animation_test1() {
+        let grid = Grid::parse(&["012", "200"], |c| Ok(c.to_digit(10).unwrap())).unwrap();
+
+        assert_eq!(
+            render(&grid, cell),
+            "\x1b[0m.\x1b[0m\x1b[31m12\x1b[0m\n\x1b[0m\x1b[31m2\x1b[0m..\x1b[0m\n"
+        );
+        assert_eq!(
+            render(&grid, |_| Cell::plain('#')),
+            "\x1b[0m###\x1b[0m\n\x1b[0m###\x1b[0m\n"
+        );
+
+        let (sender, receiver) = mpsc::channel();
+        let mut animation = Animation::new(vec![])
+            .delay(Duration::ZERO)
+            .paused(true)
+            .controls(receiver);
+
+        sender.send(String::new()).unwrap();
+        sender.send("p".to_string()).unwrap();
+        assert!(animation.frame("Step 1", &grid, cell).unwrap());
+        assert!(animation.frame("Step 2", &grid, cell).unwrap());
+
+        // Playing now, one line pauses and the next quits while paused
+        assert!(animation.frame("Step 3", &grid, cell).unwrap());
+        sender.send(String::new()).unwrap();
+        assert!(animation.frame("Step 4", &grid, cell).unwrap());
+        sender.send("q".to_string()).unwrap();
+        assert!(!animation.frame("Step 5", &grid, cell).unwrap());
+
+        let out = String::from_utf8(animation.into_inner()).unwrap();
+        assert!(out.starts_with("\x1b[2J\x1b[1;1HStep 1\n"));
+        assert_eq!(out.matches("\x1b[1;1H").count(), 5);
+        assert!(out.contains("Enter: pause"));
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{Answer, Error, ParseError};
+
+/// Name of the file that records the known answers, stored next to the input it belongs to.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// The known-correct answers for one puzzle input. A file like
+///
+/// ```toml
+/// part1 = 1696
+/// part2 = '''
+/// #..#
+/// ####'''
+/// ```
+///
+/// holds a number for part 1 and a piece of text for part 2. Parts without an entry are not known yet.
+#[derive(PartialEq, Eq, Default, Debug)]
+pub struct Answers {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+/// The outcome of comparing an answer against the known one.
+#[derive(PartialEq, Eq, Debug)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer },
+    Unknown,
+}
+
+/// The answers file that belongs to the input at `input`.
+pub fn answers_path(input: &Path) -> PathBuf {
+    input.with_file_name(ANSWERS_FILE)
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Answers, ParseError> {
+        let table = input.parse::<toml::Table>().map_err(|e| {
+            let offset = e.span().map(|s| s.start).unwrap_or_default();
+            let line = input[..offset].matches('\n').count() + 1;
+            let column = offset - input[..offset].rfind('\n').map_or(0, |n| n + 1) + 1;
+
+            ParseError::new(column, e.message()).on_line(line)
+        })?;
+
+        let answer = |key: &str| match table.get(key) {
+            None => Ok(None),
+            Some(toml::Value::Integer(n)) => Ok(Some(Answer::Number(*n))),
+            Some(toml::Value::String(s)) => Ok(Some(Answer::Text(s.clone()))),
+            Some(v) => Err(ParseError::new(
+                1,
+                format!(
+                    "{} should be a number or a string, found {}",
+                    key,
+                    v.type_str()
+                ),
+            )),
+        };
+
+        if let Some(key) = table.keys().find(|k| *k != "part1" && *k != "part2") {
+            return Err(ParseError::new(1, format!("unknown key '{}'", key)));
+        }
+
+        Ok(Answers {
+            part1: answer("part1")?,
+            part2: answer("part2")?,
+        })
+    }
+
+    /// Load the answers at `path`, a missing file means no answers are known.
+    pub fn load(path: &Path) -> Result<Answers, Error> {
+        match fs::read_to_string(path) {
+            Ok(s) => Ok(Answers::parse(&s).map_err(|e| e.in_file(path))?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(source) => Err(Error::Io {
+                path: path.to_path_buf(),
+                source,
+            }),
+        }
+    }
+
+    pub fn get(&self, part: u8) -> Option<&Answer> {
+        match part {
+            1 => self.part1.as_ref(),
+            2 => self.part2.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn check(&self, part: u8, actual: &Answer) -> Verdict {
+        match self.get(part) {
+            None => Verdict::Unknown,
+            Some(expected) if expected == actual => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{answers_path, Answers, Verdict};
+    use crate::Answer;
+
+    #[test]
+    fn answers_test1() {
+        let answers = Answers::parse("part1 = 1696\npart2 = '''\n#..#\n####'''\n").unwrap();
+
+        assert_eq!(answers.part1, Some(Answer::Number(1696)));
+        assert_eq!(answers.part2, Some(Answer::Text("#..#\n####".to_string())));
+
+        assert_eq!(answers.check(1, &Answer::Number(1696)), Verdict::Pass);
+        assert_eq!(
+            answers.check(1, &Answer::Number(1697)),
+            Verdict::Fail {
+                expected: Answer::Number(1696)
+            }
+        );
+
+        let answers = Answers::parse("part2 = 12").unwrap();
+        assert_eq!(answers.check(1, &Answer::Number(1)), Verdict::Unknown);
+
+        assert!(Answers::parse("part3 = 12").is_err());
+        assert!(Answers::parse("part1 = 1.5").is_err());
+        assert_eq!(Answers::parse("part1 = 1\npart2 = ").err().unwrap().line, 2);
+
+        assert_eq!(
+            answers_path(Path::new("days/day1/input.txt")),
+            PathBuf::from("days/day1/answers.toml")
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use num::{iter::range_inclusive, PrimInt};
+
+use crate::{Coordinate, Point2};
+
+/// What `Bounds` needs from a point, implemented for `Point2` and `Coordinate`.
+pub trait Point: Copy {
+    type Scalar: PrimInt;
+
+    /// The smallest value of every axis of both points.
+    fn lower(self, other: Self) -> Self;
+    /// The largest value of every axis of both points.
+    fn upper(self, other: Self) -> Self;
+    /// Whether every axis of `self` is at most that of `other`.
+    fn all_le(&self, other: &Self) -> bool;
+    /// Every axis increased by `n`.
+    fn grow(self, n: Self::Scalar) -> Self;
+    /// Every axis decreased by `n`.
+    fn shrink(self, n: Self::Scalar) -> Self;
+}
+
+impl<T: PrimInt> Point for Point2<T> {
+    type Scalar = T;
+
+    fn lower(self, other: Self) -> Self {
+        Point2::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    fn upper(self, other: Self) -> Self {
+        Point2::new(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    fn all_le(&self, other: &Self) -> bool {
+        self.x <= other.x && self.y <= other.y
+    }
+
+    fn grow(self, n: T) -> Self {
+        Point2::new(self.x + n, self.y + n)
+    }
+
+    fn shrink(self, n: T) -> Self {
+        Point2::new(self.x - n, self.y - n)
+    }
+}
+
+impl<T: PrimInt> Point for Coordinate<T> {
+    type Scalar = T;
+
+    fn lower(self, other: Self) -> Self {
+        Coordinate::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    fn upper(self, other: Self) -> Self {
+        Coordinate::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+
+    fn all_le(&self, other: &Self) -> bool {
+        self.x <= other.x && self.y <= other.y && self.z <= other.z
+    }
+
+    fn grow(self, n: T) -> Self {
+        Coordinate::new(self.x + n, self.y + n, self.z + n)
+    }
+
+    fn shrink(self, n: T) -> Self {
+        Coordinate::new(self.x - n, self.y - n, self.z - n)
+    }
+}
+
+/// The smallest box holding a set of points, both corners inclusive. Bounds of no points are empty and
+/// contain nothing.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Bounds<P> {
+    corners: Option<(P, P)>,
+}
+
+impl<P> Default for Bounds<P> {
+    fn default() -> Self {
+        Bounds { corners: None }
+    }
+}
+
+impl<P: Point> Bounds<P> {
+    pub fn empty() -> Bounds<P> {
+        Bounds::default()
+    }
+
+    /// The box spanned by two opposite corners, in any order.
+    pub fn new(a: P, b: P) -> Bounds<P> {
+        Bounds {
+            corners: Some((a.lower(b), a.upper(b))),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.corners.is_none()
+    }
+
+    /// The corner with the smallest values, the top left on a 2D puzzle.
+    pub fn min(&self) -> Option<P> {
+        self.corners.map(|(min, _)| min)
+    }
+
+    /// The corner with the largest values, the bottom right on a 2D puzzle.
+    pub fn max(&self) -> Option<P> {
+        self.corners.map(|(_, max)| max)
+    }
+
+    pub fn contains(&self, point: &P) -> bool {
+        self.corners
+            .is_some_and(|(min, max)| min.all_le(point) && point.all_le(&max))
+    }
+
+    /// Grow the bounds so they contain `point`.
+    pub fn include(&mut self, point: P) {
+        *self = self.union(&Bounds::new(point, point));
+    }
+
+    pub fn union(&self, other: &Bounds<P>) -> Bounds<P> {
+        match (self.corners, other.corners) {
+            (Some((a_min, a_max)), Some((b_min, b_max))) => Bounds {
+                corners: Some((a_min.lower(b_min), a_max.upper(b_max))),
+            },
+            (Some(_), None) => *self,
+            (None, _) => *other,
+        }
+    }
+
+    pub fn intersection(&self, other: &Bounds<P>) -> Bounds<P> {
+        match (self.corners, other.corners) {
+            (Some((a_min, a_max)), Some((b_min, b_max))) => {
+                let (min, max) = (a_min.upper(b_min), a_max.lower(b_max));
+
+                if min.all_le(&max) {
+                    Bounds {
+                        corners: Some((min, max)),
+                    }
+                } else {
+                    Bounds::empty()
+                }
+            }
+            _ => Bounds::empty(),
+        }
+    }
+
+    /// Grow the bounds by `n` on every side, empty bounds stay empty.
+    pub fn expand(&self, n: P::Scalar) -> Bounds<P> {
+        Bounds {
+            corners: self.corners.map(|(min, max)| (min.shrink(n), max.grow(n))),
+        }
+    }
+}
+
+impl<P: Point> FromIterator<P> for Bounds<P> {
+    fn from_iter<I: IntoIterator<Item = P>>(iter: I) -> Self {
+        let mut bounds = Bounds::empty();
+        bounds.extend(iter);
+        bounds
+    }
+}
+
+impl<P: Point> Extend<P> for Bounds<P> {
+    fn extend<I: IntoIterator<Item = P>>(&mut self, iter: I) {
+        for point in iter {
+            self.include(point);
+        }
+    }
+}
+
+/// Number of values from `min` up to and including `max`.
+fn span<T: PrimInt>(min: T, max: T) -> T {
+    max - min + T::one()
+}
+
+impl<T: PrimInt> Bounds<Point2<T>> {
+    pub fn width(&self) -> T {
+        self.corners
+            .map_or(T::zero(), |(min, max)| span(min.x, max.x))
+    }
+
+    pub fn height(&self) -> T {
+        self.corners
+            .map_or(T::zero(), |(min, max)| span(min.y, max.y))
+    }
+
+    /// Every position inside the bounds, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = Point2<T>> {
+        self.corners.into_iter().flat_map(|(min, max)| {
+            range_inclusive(min.y, max.y)
+                .flat_map(move |y| range_inclusive(min.x, max.x).map(move |x| Point2::new(x, y)))
+        })
+    }
+}
+
+impl<T: PrimInt> Bounds<Coordinate<T>> {
+    pub fn width(&self) -> T {
+        self.corners
+            .map_or(T::zero(), |(min, max)| span(min.x, max.x))
+    }
+
+    pub fn height(&self) -> T {
+        self.corners
+            .map_or(T::zero(), |(min, max)| span(min.y, max.y))
+    }
+
+    pub fn depth(&self) -> T {
+        self.corners
+            .map_or(T::zero(), |(min, max)| span(min.z, max.z))
+    }
+
+    /// Every position inside the bounds, layer by layer and row by row.
+    pub fn cells(&self) -> impl Iterator<Item = Coordinate<T>> {
+        self.corners.into_iter().flat_map(|(min, max)| {
+            range_inclusive(min.z, max.z).flat_map(move |z| {
+                range_inclusive(min.y, max.y).flat_map(move |y| {
+                    range_inclusive(min.x, max.x).map(move |x| Coordinate::new(x, y, z))
+                })
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bounds;
+    use crate::{Coordinate, Point2};
+
+    #[test]
+    fn bounds_test1() {
+        let bounds = [Point2::new(3, 1), Point2::new(1, 4), Point2::new(2, 2)]
+            .into_iter()
+            .collect::<Bounds<_>>();
+
+        assert_eq!(bounds, Bounds::new(Point2::new(3, 4), Point2::new(1, 1)));
+        assert_eq!((bounds.width(), bounds.height()), (3, 4));
+        assert!(bounds.contains(&Point2::new(1, 1)));
+        assert!(!bounds.contains(&Point2::new(0, 1)));
+        assert_eq!(bounds.cells().count(), 12);
+        assert_eq!(bounds.cells().nth(3), Some(Point2::new(1, 2)));
+
+        let other = Bounds::new(Point2::new(3, 4), Point2::new(6, 6));
+        assert_eq!(
+            bounds.union(&other),
+            Bounds::new(Point2::new(1, 1), Point2::new(6, 6))
+        );
+        assert_eq!(
+            bounds.intersection(&other),
+            Bounds::new(Point2::new(3, 4), Point2::new(3, 4))
+        );
+        assert!(bounds
+            .intersection(&Bounds::new(Point2::new(5, 5), Point2::new(6, 6)))
+            .is_empty());
+        assert_eq!(
+            bounds.expand(1),
+            Bounds::new(Point2::new(0, 0), Point2::new(4, 5))
+        );
+
+        let empty = Bounds::<Point2<i32>>::empty();
+        assert_eq!((empty.width(), empty.cells().count()), (0, 0));
+        assert!(!empty.contains(&Point2::new(0, 0)));
+        assert_eq!(empty.union(&bounds), bounds);
+        assert_eq!(empty.min(), None);
+
+        let cube = Bounds::new(Coordinate::new(0, 0, 0), Coordinate::new(1, 2, 3));
+        assert_eq!((cube.width(), cube.height(), cube.depth()), (2, 3, 4));
+        assert_eq!(cube.cells().count(), 24);
+        assert!(!cube.contains(&Coordinate::new(0, 0, 4)));
+    }
+}
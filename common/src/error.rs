@@ -0,0 +1,115 @@
+use std::{fmt, io, path::PathBuf, str::FromStr};
+
+/// Failure to load a puzzle input.
+#[derive(Debug)]
+pub enum Error {
+    Io { path: PathBuf, source: io::Error },
+    Parse(ParseError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Error::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(value: ParseError) -> Self {
+        Error::Parse(value)
+    }
+}
+
+/// Invalid puzzle input. Lines and columns count from 1, a line of 0 means the error is not tied to a line.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseError {
+    pub file: Option<PathBuf>,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error at `column` of the line being parsed. The line number is filled in by whoever knows it,
+    /// usually `parse_lines`.
+    pub fn new(column: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            file: None,
+            line: 0,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// An error about `token`, which must be a slice of `line`, reported at the column it starts in.
+    pub fn at(line: &str, token: &str, message: impl Into<String>) -> ParseError {
+        let offset = (token.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+
+        ParseError::new(if offset <= line.len() { offset + 1 } else { 1 }, message)
+    }
+
+    pub fn on_line(mut self, line: usize) -> ParseError {
+        self.line = line;
+        self
+    }
+
+    pub fn in_file(mut self, file: impl Into<PathBuf>) -> ParseError {
+        self.file = Some(file.into());
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+        if self.line > 0 {
+            write!(f, "{}:{}: ", self.line, self.column)?;
+        } else if self.file.is_some() {
+            write!(f, " ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse every line of `input` with `f`, errors are tagged with the line they occurred on.
+pub fn parse_lines<T, F>(input: &str, f: F) -> Result<Vec<T>, ParseError>
+where
+    F: Fn(&str) -> Result<T, ParseError>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(n, line)| f(line).map_err(|e| e.on_line(n + 1)))
+        .collect()
+}
+
+/// Parse a line of values separated by `separator`, like the "3,4,3,1,2" inputs. Errors carry the column
+/// of the offending value.
+pub fn parse_list<T>(line: &str, separator: char) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+{
+    line.split(separator)
+        .map(|token| {
+            token
+                .trim()
+                .parse::<T>()
+                .map_err(|_| ParseError::at(line, token, format!("invalid value '{}'", token)))
+        })
+        .collect()
+}
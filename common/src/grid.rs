@@ -0,0 +1,288 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::{Bounds, Neighborhood, ParseError, Point2};
+
+/// A rectangular puzzle stored row by row. Lookups outside the grid give `None` instead of panicking.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid of `width` columns holding `cells` row by row.
+    pub fn new(width: usize, cells: Vec<T>) -> Grid<T> {
+        assert!(
+            width > 0 && cells.len().is_multiple_of(width),
+            "{} cells do not make rows of {}",
+            cells.len(),
+            width
+        );
+
+        Grid {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    pub fn from_fn<F>(width: usize, height: usize, mut f: F) -> Grid<T>
+    where
+        F: FnMut(Point2<i64>) -> T,
+    {
+        Grid {
+            width,
+            height,
+            cells: (0..width * height)
+                .map(|n| f(Point2::new((n % width) as i64, (n / width) as i64)))
+                .collect(),
+        }
+    }
+
+    /// Parse every character of `input` with `f`. Rows must be equally long, errors are reported at their
+    /// line and column.
+    pub fn parse<F>(input: &[&str], f: F) -> Result<Grid<T>, ParseError>
+    where
+        F: Fn(char) -> Result<T, String>,
+    {
+        let width = input
+            .first()
+            .map(|line| line.chars().count())
+            .unwrap_or_default();
+        let mut cells = Vec::with_capacity(width * input.len());
+
+        for (y, line) in input.iter().enumerate() {
+            let len = line.chars().count();
+            if len != width {
+                return Err(ParseError::new(
+                    len.min(width) + 1,
+                    format!("expected {} cells, found {}", width, len),
+                )
+                .on_line(y + 1));
+            }
+
+            for (x, c) in line.chars().enumerate() {
+                cells.push(f(c).map_err(|e| ParseError::new(x + 1, e).on_line(y + 1))?);
+            }
+        }
+
+        Ok(Grid {
+            width,
+            height: input.len(),
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// All positions on the grid, empty when the grid has no cells.
+    pub fn bounds(&self) -> Bounds<Point2<i64>> {
+        if self.cells.is_empty() {
+            Bounds::empty()
+        } else {
+            Bounds::new(
+                Point2::new(0, 0),
+                Point2::new(self.width as i64 - 1, self.height as i64 - 1),
+            )
+        }
+    }
+
+    pub fn contains(&self, position: &Point2<i64>) -> bool {
+        self.offset(position).is_some()
+    }
+
+    fn offset(&self, position: &Point2<i64>) -> Option<usize> {
+        if (0..self.width as i64).contains(&position.x)
+            && (0..self.height as i64).contains(&position.y)
+        {
+            Some(position.y as usize * self.width + position.x as usize)
+        } else {
+            None
+        }
+    }
+
+    fn position(&self, offset: usize) -> Point2<i64> {
+        Point2::new((offset % self.width) as i64, (offset / self.width) as i64)
+    }
+
+    pub fn get(&self, position: &Point2<i64>) -> Option<&T> {
+        self.offset(position).map(|n| &self.cells[n])
+    }
+
+    pub fn get_mut(&mut self, position: &Point2<i64>) -> Option<&mut T> {
+        self.offset(position).map(move |n| &mut self.cells[n])
+    }
+
+    /// The neighbours of `position` in `neighborhood` that lie on the grid, with their value.
+    pub fn neighbors<'a>(
+        &'a self,
+        position: &Point2<i64>,
+        neighborhood: Neighborhood<'a>,
+    ) -> impl Iterator<Item = (Point2<i64>, &'a T)> + 'a {
+        neighborhood
+            .inside(*position, self.bounds())
+            .map(move |neighbor| (neighbor, &self[&neighbor]))
+    }
+
+    /// Like `neighbors` but the grid wraps around at its edges.
+    pub fn wrapping_neighbors<'a>(
+        &'a self,
+        position: &Point2<i64>,
+        neighborhood: Neighborhood<'a>,
+    ) -> impl Iterator<Item = (Point2<i64>, &'a T)> + 'a {
+        neighborhood
+            .wrapping(*position, self.bounds())
+            .map(move |neighbor| (neighbor, &self[&neighbor]))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(
+            x < self.width,
+            "column {} outside a grid of {}",
+            x,
+            self.width
+        );
+
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// All cells with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<i64>, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(n, cell)| (self.position(n), cell))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point2<i64>, &mut T)> {
+        let width = self.width;
+
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .map(move |(n, cell)| (Point2::new((n % width) as i64, (n / width) as i64), cell))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<&Point2<i64>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: &Point2<i64>) -> &T {
+        self.get(position)
+            .unwrap_or_else(|| panic!("{:?} outside the grid", position))
+    }
+}
+
+impl<T> IndexMut<&Point2<i64>> for Grid<T> {
+    fn index_mut(&mut self, position: &Point2<i64>) -> &mut T {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{:?} outside the grid", position))
+    }
+}
+
+/// One line per row, cells are written next to each other.
+impl<T> fmt::Display for Grid<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::{Neighborhood, Point2};
+
+    #[test]
+    fn grid_test1() {
+        let grid = Grid::parse(&["123", "456"], |c| {
+            c.to_digit(10).ok_or(format!("invalid digit '{}'", c))
+        })
+        .unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(&Point2::new(2, 1)), Some(&6));
+        assert_eq!(grid.get(&Point2::new(3, 0)), None);
+        assert_eq!(grid.get(&Point2::new(0, -1)), None);
+        assert_eq!(grid[&Point2::new(1, 0)], 2);
+
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.iter().nth(4), Some((Point2::new(1, 1), &5)));
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(grid.map(|v| v * 2), Grid::new(3, vec![2, 4, 6, 8, 10, 12]));
+
+        let corner = Point2::new(0, 0);
+        assert_eq!(
+            grid.neighbors(&corner, Neighborhood::VON_NEUMANN)
+                .map(|(_, v)| *v)
+                .collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        assert_eq!(
+            grid.wrapping_neighbors(&corner, Neighborhood::VON_NEUMANN)
+                .map(|(_, v)| *v)
+                .collect::<Vec<_>>(),
+            vec![4, 3, 2, 4]
+        );
+
+        let error = Grid::parse(&["12", "4x"], |c| {
+            c.to_digit(10).ok_or(format!("invalid digit '{}'", c))
+        })
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+
+        let error = Grid::parse(&["12", "4"], Ok).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::Error;
+
+/// Environment variable naming a directory laid out like `days/`, so the input of day 12 is read from
+/// `$ADVENT_INPUT_DIR/day12/input.txt`.
+pub const INPUT_DIR_VAR: &str = "ADVENT_INPUT_DIR";
+
+/// Name of the input file of a day, both in the day's crate and in `INPUT_DIR_VAR`.
+pub const INPUT_FILE: &str = "input.txt";
+
+/// Decide which file holds the input of `day`. An explicit path wins, then the directory named by
+/// `INPUT_DIR_VAR`, then the input file next to the day's manifest.
+pub fn resolve(day: u32, explicit: Option<&Path>, manifest_dir: &Path) -> PathBuf {
+    resolve_with(day, explicit, env::var_os(INPUT_DIR_VAR), manifest_dir)
+}
+
+/// Read a puzzle input.
+pub fn read(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn resolve_with(
+    day: u32,
+    explicit: Option<&Path>,
+    input_dir: Option<OsString>,
+    manifest_dir: &Path,
+) -> PathBuf {
+    if let Some(path) = explicit {
+        path.to_path_buf()
+    } else if let Some(dir) = input_dir.filter(|dir| !dir.is_empty()) {
+        PathBuf::from(dir)
+            .join(format!("day{}", day))
+            .join(INPUT_FILE)
+    } else {
+        manifest_dir.join(INPUT_FILE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::resolve_with;
+
+    #[test]
+    fn resolve_test1() {
+        let manifest = Path::new("/src/days/day12");
+
+        assert_eq!(
+            resolve_with(12, None, None, manifest),
+            PathBuf::from("/src/days/day12/input.txt")
+        );
+        assert_eq!(
+            resolve_with(12, None, Some("/inputs".into()), manifest),
+            PathBuf::from("/inputs/day12/input.txt")
+        );
+        assert_eq!(
+            resolve_with(12, None, Some("".into()), manifest),
+            PathBuf::from("/src/days/day12/input.txt")
+        );
+        assert_eq!(
+            resolve_with(
+                12,
+                Some(Path::new("mine.txt")),
+                Some("/inputs".into()),
+                manifest
+            ),
+            PathBuf::from("mine.txt")
+        );
+    }
+}
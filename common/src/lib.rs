@@ -1,153 +1,87 @@
-use std::{collections::HashMap, fs};
-
-use num::{
-    integer::{sqrt, Roots},
-    Integer,
-};
-
-pub fn import(name: &str) -> Vec<String> {
-    fs::read_to_string(name)
-        .unwrap()
-        .split_terminator('\n')
-        .map(|s| s.to_string())
-        .collect()
-}
-
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
-pub struct Coordinate<T> {
-    pub x: T,
-    pub y: T,
-    pub z: T,
-}
+pub mod animation;
+pub mod answers;
+mod bounds;
+mod error;
+mod grid;
+pub mod input;
+mod neighborhood;
+mod point;
+pub mod search;
+mod solution;
+
+pub use answers::{Answers, Verdict};
+pub use bounds::{Bounds, Point};
+pub use error::{parse_lines, parse_list, Error, ParseError};
+pub use grid::Grid;
+pub use neighborhood::Neighborhood;
+pub use point::{Coordinate, Direction, Point2, Point3};
+pub use solution::{Answer, Day, Registry, Solution};
 
-impl<T> Coordinate<T>
-where
-    T: num::Signed,
-    T: Copy,
-    T: std::cmp::PartialOrd,
-    T: Integer,
-    T: Roots,
-{
-    pub fn new(x: T, y: T, z: T) -> Coordinate<T> {
-        Coordinate { x, y, z }
-    }
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
 
-    pub fn manhattan(&self, other: &Self) -> T {
-        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
-    }
+    use crate::{parse_lines, parse_list, Answer, ParseError, Registry, Solution};
 
-    pub fn crow(&self, other: &Self) -> T {
-        let dx = self.x - other.x;
-        let dy = self.y - other.y;
-        let dz = self.z - other.z;
+    struct Sum(Vec<i64>);
 
-        sqrt(dx * dx + dy * dy + dz * dz)
-    }
-
-    pub fn offset(&self, x: T, y: T, z: T) -> Self {
-        Coordinate {
-            x: self.x + x,
-            y: self.y + y,
-            z: self.z + z,
+    impl Solution for Sum {
+        fn parse(input: &str) -> Result<Self, ParseError> {
+            Ok(Sum(parse_lines(input, |line| {
+                line.parse().map_err(|_| ParseError::new(1, "not a number"))
+            })?))
         }
-    }
-
-    pub fn is_inside(&self, top_left: &Coordinate<T>, bottom_right: &Coordinate<T>) -> bool {
-        assert!(top_left.x <= bottom_right.x);
-        assert!(top_left.y <= bottom_right.y);
-
-        self.x >= top_left.x
-            && self.x <= bottom_right.x
-            && self.y >= top_left.y
-            && self.y <= bottom_right.y
-    }
-}
 
-/// Enumerate the chars in the input slice and parse these as digits. Return the elements with their coordibates as key.
-pub fn enumerate_xy<F, V>(input: &[&str], f: &F) -> HashMap<Coordinate<i64>, V>
-where
-    F: Fn(i64, i64, char) -> V,
-{
-    input
-        .iter()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars().enumerate().map(move |(x, c)| {
-                (
-                    Coordinate::new(x as i64, y as i64, 0),
-                    f(x as i64, y as i64, c),
-                )
-            })
-        })
-        .collect()
-}
-
-pub fn extends<I, T>(mut iter: I) -> (Coordinate<T>, Coordinate<T>)
-where
-    I: Iterator<Item = Coordinate<T>>,
-    T: Copy,
-    T: std::cmp::PartialOrd,
-{
-    let mut tl = iter.next().expect("Empty coordinate collection");
-    let mut br = tl;
-
-    for i in iter {
-        if i.x < tl.x {
-            tl.x = i.x
-        }
-        if i.x > br.x {
-            br.x = i.x
-        }
-        if i.y < tl.y {
-            tl.y = i.y
+        fn part1(&self) -> Answer {
+            self.0.iter().sum::<i64>().into()
         }
-        if i.y > br.y {
-            br.y = i.y
+
+        fn part2(&self) -> Answer {
+            self.0.iter().product::<i64>().into()
         }
     }
-    (tl, br)
-}
 
-pub fn neighbors(
-    location: &Coordinate<i64>,
-    extends: &(Coordinate<i64>, Coordinate<i64>),
-) -> Vec<Coordinate<i64>> {
-    let mut result = vec![];
+    #[test]
+    fn registry_test1() {
+        let mut registry = Registry::default();
+
+        registry.register::<Sum>(2, "Product", "days/day2");
+        registry.register::<Sum>(1, "Sum", "days/day1");
+
+        assert_eq!(
+            registry.iter().map(|d| d.day).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert!(registry.get(3).is_none());
+
+        let day = registry.get(1).unwrap();
+        assert_eq!(day.title, "Sum");
+        assert_eq!(day.input_path(None), PathBuf::from("days/day1/input.txt"));
+
+        let solution = day.parse("2\n3\n4").unwrap();
+        assert_eq!(solution.part1(), Answer::Number(9));
+        assert_eq!(solution.part2(), Answer::Number(24));
+        assert_eq!(Answer::from(i64::MAX as u64), Answer::Number(i64::MAX));
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+
+        let error = day.parse("2\nthree\n4").err().unwrap();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.to_string(), "2:1: not a number");
+        assert_eq!(
+            error.in_file("input.txt").to_string(),
+            "input.txt:2:1: not a number"
+        );
+    }
 
-    for x in -1..=1 {
-        for y in -1..=1 {
-            let neighbor = location.offset(x, y, 0);
+    #[test]
+    fn parse_error_test1() {
+        let line = "forward x";
 
-            if neighbor.is_inside(&extends.0, &extends.1) {
-                result.push(neighbor);
-            }
-        }
-    }
-    result
-}
+        let error = ParseError::at(line, &line[8..], "invalid count");
+        assert_eq!(error.column, 9);
+        assert_eq!(error.on_line(3).to_string(), "3:9: invalid count");
 
-pub fn dfs_all<N, NB, G>(
-    current: &N,
-    success: &G,
-    neighbors: &NB,
-    visited: &mut Vec<N>,
-    paths: &mut Vec<Vec<N>>,
-) where
-    N: Clone + Eq + std::hash::Hash,
-    NB: Fn(&N, &Vec<N>) -> Vec<N>,
-    G: Fn(&N) -> bool,
-{
-    visited.push(current.clone());
-
-    if success(current) {
-        paths.push(visited.clone());
-    } else {
-        for neighbor in neighbors(current, visited) {
-            dfs_all(&neighbor, success, neighbors, visited, paths);
-        }
+        assert_eq!(parse_list::<i32>("3,4,3", ','), Ok(vec![3, 4, 3]));
+        assert_eq!(parse_list::<i32>("3,x,3", ',').err().unwrap().column, 3);
     }
-    visited.pop();
 }
-
-#[cfg(test)]
-mod tests {}
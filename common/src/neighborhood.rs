@@ -0,0 +1,119 @@
+use crate::{Bounds, Point2};
+
+/// The offsets that make up the neighbours of a position. The iterators only walk the offsets, nothing is
+/// allocated per query.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Neighborhood<'a> {
+    offsets: &'a [(i64, i64)],
+}
+
+impl Neighborhood<'static> {
+    /// The 4 orthogonal neighbours.
+    pub const VON_NEUMANN: Neighborhood<'static> = Neighborhood {
+        offsets: &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+    };
+
+    /// The 8 orthogonal and diagonal neighbours.
+    pub const MOORE: Neighborhood<'static> = Neighborhood {
+        offsets: &[
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ],
+    };
+}
+
+impl<'a> Neighborhood<'a> {
+    /// Any set of `(x, y)` offsets, like the 3x3 block including the centre or a knight's moves.
+    pub const fn stencil(offsets: &'a [(i64, i64)]) -> Neighborhood<'a> {
+        Neighborhood { offsets }
+    }
+
+    pub fn offsets(&self) -> &'a [(i64, i64)] {
+        self.offsets
+    }
+
+    /// The neighbours of `position`, without any limits.
+    pub fn around(self, position: Point2<i64>) -> impl Iterator<Item = Point2<i64>> + 'a {
+        self.offsets
+            .iter()
+            .map(move |&(x, y)| position + Point2::new(x, y))
+    }
+
+    /// The neighbours of `position` that lie within the `bounds`.
+    pub fn inside(
+        self,
+        position: Point2<i64>,
+        bounds: Bounds<Point2<i64>>,
+    ) -> impl Iterator<Item = Point2<i64>> + 'a {
+        self.around(position)
+            .filter(move |neighbor| bounds.contains(neighbor))
+    }
+
+    /// The neighbours of `position` on a torus, leaving the `bounds` on one side enters on the other.
+    pub fn wrapping(
+        self,
+        position: Point2<i64>,
+        bounds: Bounds<Point2<i64>>,
+    ) -> impl Iterator<Item = Point2<i64>> + 'a {
+        let top_left = bounds.min().expect("Cannot wrap around empty bounds");
+        let (width, height) = (bounds.width(), bounds.height());
+
+        self.around(position).map(move |neighbor| {
+            Point2::new(
+                top_left.x + (neighbor.x - top_left.x).rem_euclid(width),
+                top_left.y + (neighbor.y - top_left.y).rem_euclid(height),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Neighborhood;
+    use crate::{Bounds, Point2};
+
+    #[test]
+    fn neighborhood_test1() {
+        let bounds = Bounds::new(Point2::new(0, 0), Point2::new(4, 4));
+        let corner = Point2::new(0, 0);
+
+        assert_eq!(
+            Neighborhood::VON_NEUMANN
+                .inside(corner, bounds)
+                .collect::<Vec<_>>(),
+            vec![Point2::new(1, 0), Point2::new(0, 1)]
+        );
+        assert_eq!(Neighborhood::MOORE.inside(corner, bounds).count(), 3);
+        assert_eq!(
+            Neighborhood::MOORE
+                .inside(Point2::new(2, 2), bounds)
+                .count(),
+            8
+        );
+
+        assert_eq!(
+            Neighborhood::VON_NEUMANN
+                .wrapping(corner, bounds)
+                .collect::<Vec<_>>(),
+            vec![
+                Point2::new(0, 4),
+                Point2::new(4, 0),
+                Point2::new(1, 0),
+                Point2::new(0, 1)
+            ]
+        );
+
+        let knight = Neighborhood::stencil(&[(1, 2), (2, 1), (-1, -2)]);
+        assert_eq!(
+            knight.around(corner).collect::<Vec<_>>(),
+            vec![Point2::new(1, 2), Point2::new(2, 1), Point2::new(-1, -2)]
+        );
+        assert_eq!(knight.inside(corner, bounds).count(), 2);
+    }
+}
@@ -0,0 +1,327 @@
+use std::{
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use num::{
+    integer::{sqrt, Roots},
+    Integer, Signed,
+};
+
+use crate::{parse_list, ParseError};
+
+/// A position on a 2D puzzle, `y` grows downwards like the lines of the input. Ordered by `x`, then `y`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default, Debug)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A position in 3D space. Ordered by `x`, then `y`, then `z`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default, Debug)]
+pub struct Coordinate<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+pub type Point3<T> = Coordinate<T>;
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Point2<T> {
+        Point2 { x, y }
+    }
+}
+
+impl<T> Point2<T>
+where
+    T: Signed,
+    T: Copy,
+    T: std::cmp::PartialOrd,
+    T: Integer,
+    T: Roots,
+{
+    pub fn manhattan(&self, other: &Self) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn crow(&self, other: &Self) -> T {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+
+        sqrt(dx * dx + dy * dy)
+    }
+
+    /// The vector turned a quarter counterclockwise as seen on screen, east becomes north.
+    pub fn turn_left(&self) -> Self {
+        Point2::new(self.y, -self.x)
+    }
+
+    /// The vector turned a quarter clockwise as seen on screen, east becomes south.
+    pub fn turn_right(&self) -> Self {
+        Point2::new(-self.y, self.x)
+    }
+}
+
+impl<T> Coordinate<T> {
+    pub const fn new(x: T, y: T, z: T) -> Coordinate<T> {
+        Coordinate { x, y, z }
+    }
+}
+
+/// Axis permutation and signs of the 24 ways to turn an object in 3D, the identity first.
+const ROTATIONS: [([usize; 3], [i8; 3]); 24] = [
+    ([0, 1, 2], [1, 1, 1]),
+    ([0, 1, 2], [1, -1, -1]),
+    ([0, 1, 2], [-1, 1, -1]),
+    ([0, 1, 2], [-1, -1, 1]),
+    ([0, 2, 1], [1, 1, -1]),
+    ([0, 2, 1], [1, -1, 1]),
+    ([0, 2, 1], [-1, 1, 1]),
+    ([0, 2, 1], [-1, -1, -1]),
+    ([1, 0, 2], [1, 1, -1]),
+    ([1, 0, 2], [1, -1, 1]),
+    ([1, 0, 2], [-1, 1, 1]),
+    ([1, 0, 2], [-1, -1, -1]),
+    ([1, 2, 0], [1, 1, 1]),
+    ([1, 2, 0], [1, -1, -1]),
+    ([1, 2, 0], [-1, 1, -1]),
+    ([1, 2, 0], [-1, -1, 1]),
+    ([2, 0, 1], [1, 1, 1]),
+    ([2, 0, 1], [1, -1, -1]),
+    ([2, 0, 1], [-1, 1, -1]),
+    ([2, 0, 1], [-1, -1, 1]),
+    ([2, 1, 0], [1, 1, -1]),
+    ([2, 1, 0], [1, -1, 1]),
+    ([2, 1, 0], [-1, 1, 1]),
+    ([2, 1, 0], [-1, -1, -1]),
+];
+
+impl<T> Coordinate<T>
+where
+    T: Signed,
+    T: Copy,
+    T: std::cmp::PartialOrd,
+    T: Integer,
+    T: Roots,
+{
+    pub fn manhattan(&self, other: &Self) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    pub fn crow(&self, other: &Self) -> T {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+        let dz = self.z - other.z;
+
+        sqrt(dx * dx + dy * dy + dz * dz)
+    }
+
+    pub fn offset(&self, x: T, y: T, z: T) -> Self {
+        *self + Coordinate::new(x, y, z)
+    }
+
+    /// The position turned by one of the 24 rotations, `rotate(0)` leaves it as it is.
+    pub fn rotate(&self, rotation: usize) -> Self {
+        let (axes, signs) = ROTATIONS[rotation];
+        let axis = |n: usize| {
+            let v = [self.x, self.y, self.z][axes[n]];
+            if signs[n] < 0 {
+                -v
+            } else {
+                v
+            }
+        };
+
+        Coordinate::new(axis(0), axis(1), axis(2))
+    }
+
+    /// The position under all 24 rotations, in the order of `rotate`.
+    pub fn rotations(&self) -> impl Iterator<Item = Self> + '_ {
+        (0..ROTATIONS.len()).map(move |n| self.rotate(n))
+    }
+}
+
+/// The four ways to go on a 2D puzzle.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn turn_left(self) -> Direction {
+        Direction::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn turn_right(self) -> Direction {
+        Direction::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn reverse(self) -> Direction {
+        Direction::ALL[(self as usize + 2) % 4]
+    }
+
+    /// One step in this direction, up is towards smaller `y`.
+    pub fn offset<T: Signed>(self) -> Point2<T> {
+        match self {
+            Direction::Up => Point2::new(T::zero(), -T::one()),
+            Direction::Right => Point2::new(T::one(), T::zero()),
+            Direction::Down => Point2::new(T::zero(), T::one()),
+            Direction::Left => Point2::new(-T::one(), T::zero()),
+        }
+    }
+}
+
+/// Component wise arithmetic, and scaling by a number.
+macro_rules! impl_ops {
+    ($point:ident { $($c:ident),+ }) => {
+        impl<T: Add<Output = T>> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                $point { $($c: self.$c + rhs.$c),+ }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                $point { $($c: self.$c - rhs.$c),+ }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $point { $($c: -self.$c),+ }
+            }
+        }
+
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                $point { $($c: self.$c * rhs),+ }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $point<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$c += rhs.$c;)+
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $point<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$c -= rhs.$c;)+
+            }
+        }
+    };
+}
+
+impl_ops!(Point2 { x, y });
+impl_ops!(Coordinate { x, y, z });
+
+/// Split "1,2,3" like values into exactly `count` numbers.
+fn parse_values<T: FromStr>(s: &str, count: usize) -> Result<Vec<T>, ParseError> {
+    let values = parse_list(s, ',')?;
+
+    if values.len() != count {
+        return Err(ParseError::new(
+            1,
+            format!(
+                "expected {} values separated by ',', found {}",
+                count,
+                values.len()
+            ),
+        ));
+    }
+    Ok(values)
+}
+
+/// Parses "x,y", errors carry the column of the offending value.
+impl<T: FromStr + Copy> FromStr for Point2<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let v = parse_values(s, 2)?;
+
+        Ok(Point2::new(v[0], v[1]))
+    }
+}
+
+/// Parses "x,y,z", errors carry the column of the offending value.
+impl<T: FromStr + Copy> FromStr for Coordinate<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let v = parse_values(s, 3)?;
+
+        Ok(Coordinate::new(v[0], v[1], v[2]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{Coordinate, Direction, Point2};
+
+    #[test]
+    fn point_test1() {
+        let a = Point2::new(1, 2);
+        let b = Point2::new(4, -2);
+
+        assert_eq!(a + b, Point2::new(5, 0));
+        assert_eq!(a - b, Point2::new(-3, 4));
+        assert_eq!(-a, Point2::new(-1, -2));
+        assert_eq!(a * 3, Point2::new(3, 6));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.crow(&b), 5);
+        assert!(a < b);
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+
+        assert_eq!("3,-4".parse::<Point2<i32>>(), Ok(Point2::new(3, -4)));
+        assert_eq!("3,x".parse::<Point2<i32>>().unwrap_err().column, 3);
+        assert!("3,4,5".parse::<Point2<i32>>().is_err());
+        assert_eq!("3,4,5".parse(), Ok(Coordinate::new(3, 4, 5)));
+
+        let east = Direction::Right.offset::<i32>();
+        assert_eq!(east.turn_right(), Direction::Down.offset());
+        assert_eq!(east.turn_left(), Direction::Up.offset());
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Down.reverse(), Direction::Up);
+    }
+
+    #[test]
+    fn rotation_test1() {
+        let p = Coordinate::new(1, 2, 3);
+
+        assert_eq!(p.rotate(0), p);
+
+        let rotations = p.rotations().collect::<HashSet<_>>();
+        assert_eq!(rotations.len(), 24);
+        assert!(rotations
+            .iter()
+            .all(|r| r.manhattan(&Coordinate::new(0, 0, 0)) == 6));
+        // A mirror image is not a rotation
+        assert!(!rotations.contains(&Coordinate::new(-1, 2, 3)));
+    }
+}
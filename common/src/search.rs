@@ -0,0 +1,756 @@
+//! Graph searches over implicit graphs. A graph is nothing more than a closure returning the neighbours of a
+//! node, so the same searches run on grids, cave systems or game states.
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    iter,
+    ops::Add,
+};
+
+use num::Zero;
+
+/// What a search should do after a visitor has seen a node.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Control {
+    /// Expand the node as usual.
+    Continue,
+    /// Do not expand the node, the search goes on with the others.
+    Prune,
+    /// End the search right away.
+    Stop,
+}
+
+struct Entry<N, C> {
+    node: N,
+    parent: Option<usize>,
+    cost: C,
+}
+
+/// The nodes a search visited in visiting order, each with the node it was reached from and the cost (or
+/// depth) at which it was reached.
+pub struct SearchTree<N, C> {
+    entries: Vec<Entry<N, C>>,
+    index: HashMap<N, usize>,
+}
+
+impl<N, C> SearchTree<N, C>
+where
+    N: Clone + Eq + Hash,
+{
+    fn new() -> Self {
+        SearchTree {
+            entries: vec![],
+            index: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, node: N, parent: Option<usize>, cost: C) -> usize {
+        let n = self.entries.len();
+
+        self.index.insert(node.clone(), n);
+        self.entries.push(Entry { node, parent, cost });
+        n
+    }
+
+    /// Record a cheaper way to entry `n`, it keeps its place in the visiting order.
+    fn update(&mut self, n: usize, parent: Option<usize>, cost: C) {
+        self.entries[n].parent = parent;
+        self.entries[n].cost = cost;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.index.contains_key(node)
+    }
+
+    pub fn cost(&self, node: &N) -> Option<&C> {
+        self.index.get(node).map(|&n| &self.entries[n].cost)
+    }
+
+    pub fn parent(&self, node: &N) -> Option<&N> {
+        self.index
+            .get(node)
+            .and_then(|&n| self.entries[n].parent)
+            .map(|p| &self.entries[p].node)
+    }
+
+    /// The nodes in the order they were visited.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.entries.iter().map(|entry| &entry.node)
+    }
+
+    /// The nodes from the start up to and including `node`, `None` when the search never got there.
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        let mut current = self.index.get(node).copied();
+        let mut path = vec![];
+
+        while let Some(n) = current {
+            path.push(self.entries[n].node.clone());
+            current = self.entries[n].parent;
+        }
+
+        if path.is_empty() {
+            None
+        } else {
+            path.reverse();
+            Some(path)
+        }
+    }
+}
+
+/// Breadth first search from `start`, the visitor sees every node once together with its depth.
+pub fn bfs<N, I, FN, FV>(start: N, mut neighbors: FN, mut visit: FV) -> SearchTree<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    FN: FnMut(&N) -> I,
+    FV: FnMut(&N, &usize) -> Control,
+{
+    let mut tree = SearchTree::new();
+    let mut queue = VecDeque::from([tree.insert(start, None, 0)]);
+
+    while let Some(n) = queue.pop_front() {
+        let (node, depth) = (tree.entries[n].node.clone(), tree.entries[n].cost);
+
+        match visit(&node, &depth) {
+            Control::Stop => break,
+            Control::Prune => continue,
+            Control::Continue => {}
+        }
+
+        for neighbor in neighbors(&node) {
+            if !tree.contains(&neighbor) {
+                queue.push_back(tree.insert(neighbor, Some(n), depth + 1));
+            }
+        }
+    }
+    tree
+}
+
+/// Depth first search from `start` without recursion, the visitor sees every node once together with its
+/// depth. Neighbours are visited in the order the closure returns them.
+pub fn dfs<N, I, FN, FV>(start: N, mut neighbors: FN, mut visit: FV) -> SearchTree<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    FN: FnMut(&N) -> I,
+    FV: FnMut(&N, &usize) -> Control,
+{
+    let mut tree = SearchTree::new();
+    let mut stack = vec![(start, None, 0)];
+
+    while let Some((node, parent, depth)) = stack.pop() {
+        if tree.contains(&node) {
+            continue;
+        }
+        let n = tree.insert(node.clone(), parent, depth);
+
+        match visit(&node, &depth) {
+            Control::Stop => break,
+            Control::Prune => continue,
+            Control::Continue => {}
+        }
+
+        let pending = stack.len();
+        stack.extend(
+            neighbors(&node)
+                .into_iter()
+                .filter(|neighbor| !tree.contains(neighbor))
+                .map(|neighbor| (neighbor, Some(n), depth + 1)),
+        );
+        stack[pending..].reverse();
+    }
+    tree
+}
+
+/// A node waiting in the priority queue, the cheapest estimate comes out first.
+struct Queued<C> {
+    estimate: C,
+    cost: C,
+    n: usize,
+}
+
+impl<C: Ord> PartialEq for Queued<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<C: Ord> Eq for Queued<C> {}
+
+impl<C: Ord> PartialOrd for Queued<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C: Ord> Ord for Queued<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then_with(|| other.n.cmp(&self.n))
+    }
+}
+
+/// Dijkstra with an optional heuristic, shared by `dijkstra` and `astar`. Nodes end up in the tree when
+/// they are expanded. A node reached cheaper after that is expanded again with the new cost, which only
+/// happens with a heuristic that is admissible but not consistent.
+fn best_first<N, C, I, FN, FH, FV>(
+    start: N,
+    mut neighbors: FN,
+    mut heuristic: FH,
+    mut visit: FV,
+) -> SearchTree<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    FN: FnMut(&N) -> I,
+    FH: FnMut(&N) -> C,
+    FV: FnMut(&N, &C) -> Control,
+{
+    let mut tree = SearchTree::new();
+    // Every node seen so far with the cheapest known cost and where that came from
+    let mut nodes = vec![(start.clone(), None::<usize>)];
+    let mut best = HashMap::from([(start.clone(), (C::zero(), 0))]);
+    let mut queue = BinaryHeap::from([Queued {
+        estimate: heuristic(&start),
+        cost: C::zero(),
+        n: 0,
+    }]);
+    // Tree index of every node in `nodes` once expanded
+    let mut expanded = HashMap::new();
+
+    while let Some(Queued { cost, n, .. }) = queue.pop() {
+        let (node, from) = nodes[n].clone();
+        // Queued before a cheaper cost was found, that one is queued as well
+        if best[&node].0 < cost {
+            continue;
+        }
+        match expanded.get(&n) {
+            Some(&t) => tree.update(t, from, cost),
+            None => {
+                expanded.insert(n, tree.insert(node.clone(), from, cost));
+            }
+        }
+
+        match visit(&node, &cost) {
+            Control::Stop => break,
+            Control::Prune => continue,
+            Control::Continue => {}
+        }
+
+        let parent = Some(expanded[&n]);
+        for (neighbor, step) in neighbors(&node) {
+            let cost = cost + step;
+
+            let m = match best.get(&neighbor) {
+                Some(&(known, _)) if known <= cost => continue,
+                Some(&(_, m)) => {
+                    nodes[m].1 = parent;
+                    m
+                }
+                None => {
+                    nodes.push((neighbor.clone(), parent));
+                    nodes.len() - 1
+                }
+            };
+            queue.push(Queued {
+                estimate: cost + heuristic(&neighbor),
+                cost,
+                n: m,
+            });
+            best.insert(neighbor, (cost, m));
+        }
+    }
+    tree
+}
+
+/// Cheapest costs from `start`, the visitor sees every node once its cost is final, cheapest first.
+pub fn dijkstra<N, C, I, FN, FV>(start: N, neighbors: FN, visit: FV) -> SearchTree<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    FN: FnMut(&N) -> I,
+    FV: FnMut(&N, &C) -> Control,
+{
+    best_first(start, neighbors, |_| C::zero(), visit)
+}
+
+/// The cheapest path from `start` to the first node satisfying `goal` and its cost. The `heuristic` must
+/// never overestimate the remaining cost. When it is also consistent, never dropping by more than the
+/// cost of a step, every node is expanded once; otherwise nodes reached cheaper later are expanded again.
+pub fn astar<N, C, I, FN, FH, FG>(
+    start: N,
+    neighbors: FN,
+    heuristic: FH,
+    mut goal: FG,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    FN: FnMut(&N) -> I,
+    FH: FnMut(&N) -> C,
+    FG: FnMut(&N) -> bool,
+{
+    let mut found = None;
+
+    let tree = best_first(start, neighbors, heuristic, |node, _| {
+        if goal(node) {
+            found = Some(node.clone());
+            Control::Stop
+        } else {
+            Control::Continue
+        }
+    });
+
+    let found = found?;
+    Some((tree.path(&found)?, *tree.cost(&found)?))
+}
+
+/// Shortest path between `start` and `goal` growing a breadth first search from both ends. Only valid
+/// when every edge goes both ways, the closure is used in both directions.
+pub fn bidirectional_bfs<N, I, FN>(start: N, goal: N, mut neighbors: FN) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    FN: FnMut(&N) -> I,
+{
+    if start == goal {
+        return Some(vec![start]);
+    }
+
+    // Each side maps the nodes it reached to the node it reached them from
+    let mut sides = [
+        HashMap::from([(start.clone(), None)]),
+        HashMap::from([(goal.clone(), None)]),
+    ];
+    let mut frontiers = [vec![start], vec![goal]];
+
+    let meeting = 'search: loop {
+        // Grow the smaller frontier by one layer
+        let side = if frontiers[0].len() <= frontiers[1].len() {
+            0
+        } else {
+            1
+        };
+        if frontiers[side].is_empty() {
+            return None;
+        }
+
+        let mut next = vec![];
+        for node in std::mem::take(&mut frontiers[side]) {
+            for neighbor in neighbors(&node) {
+                if sides[side].contains_key(&neighbor) {
+                    continue;
+                }
+                sides[side].insert(neighbor.clone(), Some(node.clone()));
+
+                if sides[1 - side].contains_key(&neighbor) {
+                    break 'search neighbor;
+                }
+                next.push(neighbor);
+            }
+        }
+        frontiers[side] = next;
+    };
+
+    let walk = |side: &HashMap<N, Option<N>>| {
+        iter::successors(Some(meeting.clone()), |node| side[node].clone()).collect::<Vec<_>>()
+    };
+
+    let mut path = walk(&sides[0]);
+    path.reverse();
+    path.extend(walk(&sides[1]).into_iter().skip(1));
+    Some(path)
+}
+
+/// All nodes reachable from `start`, `start` included, in breadth first order.
+pub fn flood_fill<N, I, FN>(start: N, neighbors: FN) -> Vec<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    FN: FnMut(&N) -> I,
+{
+    bfs(start, neighbors, |_, _| Control::Continue)
+        .entries
+        .into_iter()
+        .map(|entry| entry.node)
+        .collect()
+}
+
+/// Split `nodes` into groups of nodes that reach each other, in the order the groups are first seen.
+pub fn connected_components<N, I, FN>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbors: FN,
+) -> Vec<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    FN: FnMut(&N) -> I,
+{
+    let mut seen = HashSet::new();
+    let mut components = vec![];
+
+    for node in nodes {
+        if seen.contains(&node) {
+            continue;
+        }
+        let component = flood_fill(node, &mut neighbors);
+
+        seen.extend(component.iter().cloned());
+        components.push(component);
+    }
+    components
+}
+
+/// Every path from `start` to a node satisfying `goal`, found depth first without recursion. The
+/// neighbours may depend on the whole path so far, which allows rules like "visit at most once". Paths
+/// end at the first goal they reach and are produced one at a time, only the current path is kept.
+pub fn all_paths<N, I, FN, FG>(start: N, neighbors: FN, goal: FG) -> Paths<N, I::IntoIter, FN, FG>
+where
+    N: Clone,
+    I: IntoIterator<Item = N>,
+    FN: FnMut(&[N]) -> I,
+    FG: FnMut(&N) -> bool,
+{
+    Paths {
+        path: vec![start],
+        pending: vec![],
+        started: false,
+        neighbors,
+        goal,
+    }
+}
+
+/// Iterator returned by `all_paths`.
+pub struct Paths<N, J, FN, FG> {
+    path: Vec<N>,
+    // The neighbours still to try for every node on the path
+    pending: Vec<J>,
+    started: bool,
+    neighbors: FN,
+    goal: FG,
+}
+
+impl<N, I, FN, FG> Iterator for Paths<N, I::IntoIter, FN, FG>
+where
+    N: Clone,
+    I: IntoIterator<Item = N>,
+    FN: FnMut(&[N]) -> I,
+    FG: FnMut(&N) -> bool,
+{
+    type Item = Vec<N>;
+
+    fn next(&mut self) -> Option<Vec<N>> {
+        if !self.started {
+            self.started = true;
+
+            if (self.goal)(&self.path[0]) {
+                return Some(self.path.clone());
+            }
+            self.pending.push((self.neighbors)(&self.path).into_iter());
+        }
+
+        while let Some(next) = self.pending.last_mut() {
+            let Some(node) = next.next() else {
+                self.pending.pop();
+                self.path.pop();
+                continue;
+            };
+
+            self.path.push(node);
+            if (self.goal)(&self.path[self.path.len() - 1]) {
+                let found = self.path.clone();
+                self.path.pop();
+                return Some(found);
+            }
+            self.pending.push((self.neighbors)(&self.path).into_iter());
+        }
+        None
+    }
+}
+
+/// A walk through a graph that keeps state about the path it is on, for `walk_paths`. The walk is told
+/// about every node added to the path and every node taken off it again, so the state always matches the
+/// path without having to look at it.
+pub trait PathWalk<N> {
+    /// The neighbours of `node`, tried in this order.
+    fn neighbors(&self, node: &N) -> &[N];
+
+    /// Whether the path may go on to `node` from where it is now.
+    fn allows(&self, node: &N) -> bool;
+
+    fn is_goal(&self, node: &N) -> bool;
+
+    fn enter(&mut self, node: &N);
+
+    fn leave(&mut self, node: &N);
+}
+
+/// Every path from `start` to a goal of `walk`, like `all_paths` but with the rules kept by the walk.
+/// Nothing is allocated per node, only the paths produced are.
+pub fn walk_paths<N, W>(start: N, walk: W) -> WalkPaths<N, W>
+where
+    N: Clone,
+    W: PathWalk<N>,
+{
+    WalkPaths {
+        path: vec![start],
+        tried: vec![],
+        started: false,
+        walk,
+    }
+}
+
+/// Iterator returned by `walk_paths`.
+pub struct WalkPaths<N, W> {
+    path: Vec<N>,
+    // How many neighbours were tried for every node on the path
+    tried: Vec<usize>,
+    started: bool,
+    walk: W,
+}
+
+impl<N, W> Iterator for WalkPaths<N, W>
+where
+    N: Clone,
+    W: PathWalk<N>,
+{
+    type Item = Vec<N>;
+
+    fn next(&mut self) -> Option<Vec<N>> {
+        if !self.started {
+            self.started = true;
+
+            self.walk.enter(&self.path[0]);
+            if self.walk.is_goal(&self.path[0]) {
+                return Some(self.path.clone());
+            }
+            self.tried.push(0);
+        }
+
+        while let Some(tried) = self.tried.last_mut() {
+            let node = &self.path[self.path.len() - 1];
+            let Some(next) = self.walk.neighbors(node).get(*tried).cloned() else {
+                self.tried.pop();
+                let node = self.path.pop().unwrap();
+                self.walk.leave(&node);
+                continue;
+            };
+            *tried += 1;
+
+            if !self.walk.allows(&next) {
+                continue;
+            }
+            self.walk.enter(&next);
+            self.path.push(next);
+
+            if self.walk.is_goal(&self.path[self.path.len() - 1]) {
+                let found = self.path.clone();
+                let node = self.path.pop().unwrap();
+                self.walk.leave(&node);
+                return Some(found);
+            }
+            self.tried.push(0);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        all_paths, astar, bfs, bidirectional_bfs, connected_components, dfs, dijkstra, flood_fill,
+        walk_paths, Control, PathWalk,
+    };
+
+    // Nodes 0..=9 on a line with a cheap shortcut 0 -> 5 costing 2, and an island 20 - 21
+    fn line(n: &i32) -> Vec<(i32, u32)> {
+        let mut r = vec![];
+
+        if (1..=9).contains(n) {
+            r.push((n - 1, 1));
+        }
+        if (0..9).contains(n) {
+            r.push((n + 1, 1));
+        }
+        match n {
+            0 => r.push((5, 2)),
+            5 => r.push((0, 2)),
+            20 => r.push((21, 1)),
+            21 => r.push((20, 1)),
+            _ => {}
+        }
+        r
+    }
+
+    fn unweighted(n: &i32) -> Vec<i32> {
+        line(n).into_iter().map(|(m, _)| m).collect()
+    }
+
+    #[test]
+    fn bfs_test1() {
+        let tree = bfs(0, unweighted, |_, _| Control::Continue);
+        assert_eq!(tree.len(), 10);
+        assert_eq!(tree.cost(&9), Some(&5));
+        assert_eq!(tree.path(&7), Some(vec![0, 5, 6, 7]));
+        assert_eq!(tree.path(&20), None);
+
+        let tree = bfs(0, unweighted, |&n, _| {
+            if n == 5 {
+                Control::Prune
+            } else {
+                Control::Continue
+            }
+        });
+        // 5 is found but never expanded, so nothing beyond it is
+        assert!(tree.contains(&5) && !tree.contains(&6));
+    }
+
+    #[test]
+    fn dfs_test1() {
+        let tree = dfs(0, unweighted, |&n, _| {
+            if n == 3 {
+                Control::Stop
+            } else {
+                Control::Continue
+            }
+        });
+        assert_eq!(tree.nodes().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn dijkstra_test1() {
+        let tree = dijkstra(0, line, |_, _| Control::Continue);
+        assert_eq!(tree.cost(&4), Some(&3));
+        assert_eq!(tree.cost(&9), Some(&6));
+        assert_eq!(tree.parent(&6), Some(&5));
+    }
+
+    #[test]
+    fn astar_test1() {
+        assert_eq!(
+            astar(9, line, |&n| n.abs_diff(1) / 2, |&n| n == 1),
+            Some((vec![9, 8, 7, 6, 5, 0, 1], 7))
+        );
+        assert_eq!(astar(0, line, |_| 0, |&n| n == 20), None);
+
+        // The heuristic of 'a' is admissible, 4 is exactly its cost to 'g', but not consistent: it drops by
+        // 4 on a step costing 1. So 'c' is expanded first through the expensive edge and again once the
+        // cheaper way through 'a' shows up.
+        let edges = |n: &char| match n {
+            's' => vec![('a', 1), ('c', 3)],
+            'a' => vec![('c', 1)],
+            'c' => vec![('g', 3)],
+            _ => vec![],
+        };
+        let heuristic = |n: &char| if *n == 'a' { 4 } else { 0 };
+        assert_eq!(
+            astar('s', edges, heuristic, |&n| n == 'g'),
+            Some((vec!['s', 'a', 'c', 'g'], 5))
+        );
+    }
+
+    #[test]
+    fn bidirectional_bfs_test1() {
+        assert_eq!(
+            bidirectional_bfs(1, 7, unweighted).map(|p| p.len()),
+            Some(5)
+        );
+        assert_eq!(bidirectional_bfs(3, 3, unweighted), Some(vec![3]));
+        assert_eq!(bidirectional_bfs(3, 21, unweighted), None);
+    }
+
+    #[test]
+    fn flood_fill_test1() {
+        assert_eq!(flood_fill(21, unweighted), vec![21, 20]);
+        assert_eq!(
+            connected_components([20, 3, 21, 7], unweighted)
+                .iter()
+                .map(|c| c.len())
+                .collect::<Vec<_>>(),
+            vec![2, 10]
+        );
+    }
+
+    #[test]
+    fn all_paths_test1() {
+        // Simple paths from 0 to 9 never visit a node twice
+        let paths = all_paths(
+            0,
+            |path: &[i32]| {
+                unweighted(path.last().unwrap())
+                    .into_iter()
+                    .filter(|n| !path.contains(n))
+                    .collect::<Vec<_>>()
+            },
+            |&n| n == 9,
+        )
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            paths,
+            vec![vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9], vec![0, 5, 6, 7, 8, 9]]
+        );
+        assert_eq!(
+            all_paths(4, |_: &[i32]| vec![], |&n| n == 4).collect::<Vec<_>>(),
+            vec![vec![4]]
+        );
+    }
+
+    // Simple paths that keep track of the nodes on them, instead of searching the path
+    struct Simple {
+        neighbors: Vec<Vec<i32>>,
+        on_path: Vec<bool>,
+    }
+
+    impl PathWalk<i32> for Simple {
+        fn neighbors(&self, node: &i32) -> &[i32] {
+            &self.neighbors[*node as usize]
+        }
+
+        fn allows(&self, node: &i32) -> bool {
+            !self.on_path[*node as usize]
+        }
+
+        fn is_goal(&self, node: &i32) -> bool {
+            *node == 9
+        }
+
+        fn enter(&mut self, node: &i32) {
+            self.on_path[*node as usize] = true;
+        }
+
+        fn leave(&mut self, node: &i32) {
+            self.on_path[*node as usize] = false;
+        }
+    }
+
+    #[test]
+    fn walk_paths_test1() {
+        let walk = Simple {
+            neighbors: (0..10).map(|n| unweighted(&n)).collect(),
+            on_path: vec![false; 10],
+        };
+        let mut paths = walk_paths(0, walk);
+
+        assert_eq!(paths.next(), Some(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]));
+        assert_eq!(paths.next(), Some(vec![0, 5, 6, 7, 8, 9]));
+        assert_eq!(paths.next(), None);
+        // Every node was left again
+        assert!(paths.walk.on_path.iter().all(|&on| !on));
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::{input, ParseError};
+
+/// The answer to one part of a puzzle.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Number(value)
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer::Number(value as i64)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Number(value as i64)
+    }
+}
+
+/// Numbers beyond `i64::MAX` become text, that way they are still shown in full.
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        match i64::try_from(value) {
+            Ok(n) => Answer::Number(n),
+            Err(_) => Answer::Text(value.to_string()),
+        }
+    }
+}
+
+/// Numbers beyond `i64::MAX` become text, that way they are still shown in full.
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        match i64::try_from(value) {
+            Ok(n) => Answer::Number(n),
+            Err(_) => Answer::Text(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+/// A puzzle solution. `parse` turns the raw puzzle input into the solution, after which both parts can
+/// be answered from it.
+pub trait Solution {
+    fn parse(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized;
+
+    fn part1(&self) -> Answer;
+
+    fn part2(&self) -> Answer;
+}
+
+/// A registered day: its number, title and where to find its input.
+pub struct Day {
+    pub day: u32,
+    pub title: &'static str,
+    /// Manifest directory of the day's crate, its input is stored next to it
+    pub manifest_dir: &'static str,
+    parse: fn(&str) -> Result<Box<dyn Solution>, ParseError>,
+}
+
+impl Day {
+    /// The input file to use for this day, see `input::resolve`.
+    pub fn input_path(&self, explicit: Option<&Path>) -> PathBuf {
+        input::resolve(self.day, explicit, Path::new(self.manifest_dir))
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Solution>, ParseError> {
+        (self.parse)(input)
+    }
+}
+
+/// All days known to the harness, ordered by day number.
+#[derive(Default)]
+pub struct Registry {
+    days: Vec<Day>,
+}
+
+impl Registry {
+    /// Register solution `S` for `day`. Pass `env!("CARGO_MANIFEST_DIR")` as `manifest_dir` so the input
+    /// is found next to the day's manifest regardless of the working directory.
+    pub fn register<S>(&mut self, day: u32, title: &'static str, manifest_dir: &'static str)
+    where
+        S: Solution + 'static,
+    {
+        assert!(self.get(day).is_none(), "Day {} registered twice", day);
+
+        self.days.push(Day {
+            day,
+            title,
+            manifest_dir,
+            parse: |input| Ok(Box::new(S::parse(input)?)),
+        });
+        self.days.sort_by_key(|d| d.day);
+    }
+
+    pub fn get(&self, day: u32) -> Option<&Day> {
+        self.days.iter().find(|d| d.day == day)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Day> {
+        self.days.iter()
+    }
+}
@@ -1,3 +1,5 @@
+use common::{parse_lines, Answer, ParseError, Registry, Solution};
+
 fn find_descending_1(a: &[i64]) -> usize {
     a.windows(2).filter(|w| w[0] < w[1]).count()
 }
@@ -6,27 +8,29 @@ fn find_descending_2(a: &[i64]) -> usize {
     find_descending_1(&a.windows(3).map(|w| w.iter().sum()).collect::<Vec<i64>>())
 }
 
-fn part1() {
-    let m = find_descending_1(
-        &common::import("days/day1/input.txt")
-            .iter()
-            .map(|a| a.parse::<i64>().unwrap())
-            .collect::<Vec<_>>(),
-    );
-    println!("Day 1 part 1 : Descending : {}", m);
+struct SonarSweep {
+    depths: Vec<i64>,
 }
 
-fn part2() {
-    let m = find_descending_2(
-        &common::import("days/day1/input.txt")
-            .iter()
-            .map(|a| a.parse::<i64>().unwrap())
-            .collect::<Vec<_>>(),
-    );
-    println!("Day 1 part 2 : Descending : {}", m);
+impl Solution for SonarSweep {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(SonarSweep {
+            depths: parse_lines(input, |a| {
+                a.parse::<i64>()
+                    .map_err(|e| ParseError::new(1, format!("invalid depth '{}' : {}", a, e)))
+            })?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        find_descending_1(&self.depths).into()
+    }
+
+    fn part2(&self) -> Answer {
+        find_descending_2(&self.depths).into()
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<SonarSweep>(1, "Sonar Sweep", env!("CARGO_MANIFEST_DIR"));
 }
@@ -1,3 +1,5 @@
+use common::{parse_lines, Answer, ParseError, Registry, Solution};
+
 fn is_opening_brace(c: char) -> bool {
     c == '(' || c == '[' || c == '{' || c == '<'
 }
@@ -82,46 +84,60 @@ fn collect_autocomplete_line(input: &str) -> Option<Vec<char>> {
     Some(stack.into_iter().map(matching_brace).collect())
 }
 
-fn part1() {
-    let s = std::fs::read_to_string("days/day10/input.txt").unwrap();
-    let input = s.split_terminator('\n').collect::<Vec<_>>();
+struct SyntaxScoring {
+    lines: Vec<String>,
+}
+
+impl Solution for SyntaxScoring {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let lines = parse_lines(input, |line| match line.find(|c| !"()[]{}<>".contains(c)) {
+            Some(position) => Err(ParseError::new(position + 1, "expected a brace")),
+            None => Ok(line.to_string()),
+        })?;
+        if lines.is_empty() {
+            return Err(ParseError::new(1, "no lines to check"));
+        }
 
-    let score = total_syntax_score(&collect_invalid_tokens(&input));
+        Ok(SyntaxScoring { lines })
+    }
 
-    println!("Day 10 part 1 : Total syntax score {}", score);
-}
+    fn part1(&self) -> Answer {
+        let input = self.lines.iter().map(|s| s.as_str()).collect::<Vec<_>>();
 
-fn part2() {
-    let s = std::fs::read_to_string("days/day10/input.txt").unwrap();
-    let input = s.split_terminator('\n').collect::<Vec<_>>();
+        total_syntax_score(&collect_invalid_tokens(&input)).into()
+    }
 
-    let mut autocomplete = vec![];
+    fn part2(&self) -> Answer {
+        let mut autocomplete = vec![];
 
-    for line in input {
-        if let Some(ac) = collect_autocomplete_line(line) {
-            autocomplete.push(ac);
+        for line in &self.lines {
+            if let Some(ac) = collect_autocomplete_line(line) {
+                autocomplete.push(ac);
+            }
         }
-    }
-    autocomplete.sort_by_key(|a| total_autocomplete_score(a));
+        autocomplete.sort_by_key(|a| total_autocomplete_score(a));
 
-    println!(
-        "Day 10 part 2 : Middle autocomplete score {}",
-        total_autocomplete_score(&autocomplete[autocomplete.len() / 2])
-    );
+        // Every line may be corrupted, then there is nothing to complete
+        match autocomplete.get(autocomplete.len() / 2) {
+            Some(middle) => total_autocomplete_score(middle).into(),
+            None => "no incomplete lines".into(),
+        }
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<SyntaxScoring>(10, "Syntax Scoring", env!("CARGO_MANIFEST_DIR"));
 }
 
 #[cfg(test)]
 mod tests {
     use std::vec;
 
+    use common::{Answer, Solution};
+
     use crate::{
         collect_autocomplete_line, collect_invalid_tokens, total_autocomplete_score,
-        total_syntax_score,
+        total_syntax_score, SyntaxScoring,
     };
 
     static TEST_INPUT: &[&str] = &[
@@ -165,7 +181,7 @@ mod tests {
         assert_eq!(total_autocomplete_score(&autocomplete[3]), 995444);
         assert_eq!(total_autocomplete_score(&autocomplete[4]), 294);
 
-        autocomplete.sort_by(|a, b| total_autocomplete_score(a).cmp(&total_autocomplete_score(b)));
+        autocomplete.sort_by_key(|a| total_autocomplete_score(a));
 
         assert_eq!(total_autocomplete_score(&autocomplete[0]), 294);
         assert_eq!(total_autocomplete_score(&autocomplete[4]), 1480781);
@@ -175,4 +191,18 @@ mod tests {
             288957
         );
     }
+
+    #[test]
+    fn part2_test2() {
+        assert_eq!(
+            SyntaxScoring::parse("").err().map(|e| e.to_string()),
+            Some("no lines to check".into())
+        );
+
+        let corrupted = SyntaxScoring::parse("(]\n<)").unwrap();
+        assert_eq!(
+            corrupted.part2(),
+            Answer::Text("no incomplete lines".into())
+        );
+    }
 }
@@ -1,143 +1,65 @@
-use std::collections::HashMap;
-
-use common::{enumerate_xy, extends, neighbors, Coordinate};
-
-type Map = HashMap<Coordinate<i64>, Octopus>;
-
-fn step_map(map: &mut Map) -> usize {
-    let mut result = 0;
-
-    let extends = extends(map.keys().copied());
-
-    // A: Increase energy level of all octopuses by one
-    map.iter_mut()
-        .for_each(|(_, octopus)| octopus.energy_level += 1);
-
-    loop {
-        // B: Find the ones that should flash (only once per turn)
-        let flashes = map
-            .iter()
-            .filter_map(|(position, octopus)| {
-                if !octopus.flashed && octopus.energy_level > 9 {
-                    Some(*position)
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
-
-        result += flashes.len();
-
-        // D: repeat for as long as octopuses keep flashing
-        if flashes.is_empty() {
-            break;
-        }
-
-        // C: Increase energy level for all their neighbors
-        for f in flashes.iter() {
-            map.entry(*f).and_modify(|o| o.flashed = true);
-
-            let locations: Vec<Coordinate<i64>> = neighbors(f, &extends);
-
-            for l in locations {
-                map.entry(l).and_modify(|octopus| {
-                    octopus.energy_level += 1;
-                });
-            }
-        }
-    }
-
-    map.iter_mut().for_each(|(_, octopus)| {
-        if octopus.flashed {
-            octopus.energy_level = 0
-        }
-        octopus.flashed = false
-    });
-
-    result
-}
+mod simulation;
+
+use common::{
+    animation::{Cell, GraphicRendition},
+    Answer, ParseError, Registry, Solution,
+};
+pub use simulation::{Cycle, FlashSimulation, StepStats};
+
+/// Octopuses that just flashed light up, the others are dim.
+pub fn octopus_cell(energy: &u32) -> Cell {
+    const FLASHED: &[GraphicRendition] = &[
+        GraphicRendition::HighIntensity,
+        GraphicRendition::YellowForeground,
+    ];
+    const DIM: &[GraphicRendition] = &[GraphicRendition::LowIntensity];
 
-fn print_map(map: &Map) {
-    let extends = extends(map.keys().copied());
-    for y in extends.0.y..=extends.1.y {
-        for x in extends.0.x..=extends.1.x {
-            print!(
-                "{}",
-                map.get(&Coordinate::new(x, y, 0)).expect("").energy_level
-            );
-        }
-        println!();
+    match char::from_digit(*energy, 10) {
+        Some('0') => Cell::new('0', FLASHED),
+        Some(digit) => Cell::new(digit, DIM),
+        None => Cell::new('+', DIM),
     }
 }
 
-struct Octopus {
-    energy_level: i32,
-    flashed: bool,
+struct DumboOctopus {
+    simulation: FlashSimulation,
 }
 
-fn part1() {
-    let s = std::fs::read_to_string("days/day11/input.txt").unwrap();
-    let input = s.split_terminator('\n').collect::<Vec<_>>();
-
-    let mut map = enumerate_xy(&input, &|_, _, c| Octopus {
-        energy_level: c.to_digit(10).expect("Invalid digit") as i32,
-        flashed: false,
-    });
-
-    let mut flashed = 0;
-    for _ in 1..=100 {
-        flashed += step_map(&mut map);
-
-        // println!(
-        //     "\x1B[2J{}Step {}: flashed {} times",
-        //     ansi_control_codes::control_sequences::CUP(0.into(), 0.into()),
-        //     steps,
-        //     flashed
-        // );
-
-        // print_map(&map);
+impl Solution for DumboOctopus {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(DumboOctopus {
+            simulation: FlashSimulation::parse(input)?,
+        })
     }
 
-    println!("Day 11 part 1 : Total octopus flashes {}", flashed);
-}
-
-fn part2() {
-    let s = std::fs::read_to_string("days/day11/input.txt").unwrap();
-    let input = s.split_terminator('\n').collect::<Vec<_>>();
-
-    let mut map = enumerate_xy(&input, &|_, _, c| Octopus {
-        energy_level: c.to_digit(10).expect("Invalid digit") as i32,
-        flashed: false,
-    });
-
-    let mut step = 1;
+    fn part1(&self) -> Answer {
+        let mut simulation = self.simulation.clone();
 
-    loop {
-        if step_map(&mut map) == 100 {
-            break;
+        let mut flashed = 0;
+        for _ in 1..=100 {
+            flashed += simulation.step().flashes;
         }
 
-        // println!(
-        //     "\x1B[2J{}",
-        //     ansi_control_codes::control_sequences::CUP(0.into(), 0.into())
-        // );
-
-        // print_map(&map);
-
-        step += 1;
+        flashed.into()
     }
 
-    println!("Day 11 part 2 : All octopuses flash at step {}", step);
+    fn part2(&self) -> Answer {
+        match self.simulation.first_synchronised() {
+            Some(step) => step.into(),
+            None => "never".into(),
+        }
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<DumboOctopus>(11, "Dumbo Octopus", env!("CARGO_MANIFEST_DIR"));
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{enumerate_xy, print_map, step_map, Octopus};
+    use common::{Grid, Neighborhood};
+
+    use crate::{octopus_cell, Cycle, FlashSimulation};
 
     static TEST_INPUT: &[&str] = &[
         "5483143223",
@@ -152,42 +74,28 @@ mod tests {
         "5283751526",
     ];
 
+    fn parse(input: &[&str]) -> Grid<u32> {
+        Grid::parse(input, |c| Ok(c.to_digit(10).expect("Invalid digit"))).unwrap()
+    }
+
     #[test]
     fn part1_test1() {
-        let mut map = enumerate_xy(TEST_INPUT, &|_, _, c| Octopus {
-            energy_level: c.to_digit(10).expect("Invalid digit") as i32,
-            flashed: false,
-        });
+        let mut simulation = FlashSimulation::parse(&TEST_INPUT.join("\n")).unwrap();
 
-        assert_eq!(map.len(), 10 * 10);
+        assert_eq!(
+            simulation.energy().width() * simulation.energy().height(),
+            10 * 10
+        );
 
         let mut flashed = 0;
-        for steps in 1..=10 {
-            flashed += step_map(&mut map);
-
-            println!(
-                "\x1B[2J{}Step {}: flashed {} times",
-                ansi_control_codes::control_sequences::CUP(0.into(), 0.into()),
-                steps,
-                flashed
-            );
-
-            print_map(&map);
+        for _ in 1..=10 {
+            flashed += simulation.step().flashes;
         }
 
         assert_eq!(flashed, 204);
 
-        for steps in 11..=100 {
-            flashed += step_map(&mut map);
-
-            println!(
-                "\x1B[2J{}Step {}: flashed {} times",
-                ansi_control_codes::control_sequences::CUP(0.into(), 0.into()),
-                steps,
-                flashed
-            );
-
-            print_map(&map);
+        for _ in 11..=100 {
+            flashed += simulation.step().flashes;
         }
 
         assert_eq!(flashed, 1656);
@@ -195,20 +103,90 @@ mod tests {
 
     #[test]
     fn part2_test1() {
-        let mut map = enumerate_xy(TEST_INPUT, &|_, _, c| Octopus {
-            energy_level: c.to_digit(10).expect("Invalid digit") as i32,
-            flashed: false,
-        });
+        let mut simulation = FlashSimulation::parse(&TEST_INPUT.join("\n")).unwrap();
 
-        let mut step = 1;
+        assert_eq!(simulation.first_synchronised(), Some(195));
 
-        loop {
-            if step_map(&mut map) == 100 {
-                break;
+        let stats = simulation.steps().find(|stats| stats.synchronised).unwrap();
+        assert_eq!((stats.step, stats.flashes), (195, 100));
+
+        // All at 0 after flashing together, from then on they flash together every 10 steps
+        assert_eq!(simulation.first_synchronised(), Some(10));
+        assert_eq!(
+            simulation.cycle(),
+            Cycle {
+                start: 0,
+                length: 10
             }
-            step += 1;
-        }
+        );
+    }
 
-        assert_eq!(step, 195);
+    #[test]
+    fn octopus_cell_test1() {
+        assert_eq!(octopus_cell(&0).symbol, '0');
+        assert_ne!(octopus_cell(&0).rendition, octopus_cell(&5).rendition);
+        assert_eq!(octopus_cell(&12).symbol, '+');
+    }
+
+    #[test]
+    fn simulation_test1() {
+        // On its own a single octopus flashes every 10 steps, the 1 never catches up with the 0
+        let simulation = FlashSimulation::new(parse(&["01"]))
+            .unwrap()
+            .neighborhood(Neighborhood::stencil(&[]));
+        assert_eq!(simulation.first_synchronised(), None);
+        assert_eq!(
+            simulation.cycle(),
+            Cycle {
+                start: 0,
+                length: 10
+            }
+        );
+
+        // Next to each other the flash of the 1 pushes the 0 over
+        let simulation = FlashSimulation::new(parse(&["01"])).unwrap();
+        assert_eq!(simulation.first_synchronised(), Some(9));
+
+        let mut simulation = FlashSimulation::new(parse(&["000"])).unwrap().threshold(1);
+        assert!(simulation
+            .steps()
+            .take(3)
+            .all(|stats| stats.flashes == 0 || stats.synchronised));
+        assert_eq!(
+            simulation.cycle(),
+            Cycle {
+                start: 0,
+                length: 2
+            }
+        );
+
+        // A flash at the left edge reaches the right edge only when wrapping
+        let grid = parse(&["9000"]);
+        let mut simulation = FlashSimulation::new(grid.clone())
+            .unwrap()
+            .neighborhood(Neighborhood::VON_NEUMANN);
+        simulation.step();
+        assert_eq!(simulation.energy(), &parse(&["0211"]));
+
+        let mut simulation = FlashSimulation::new(grid)
+            .unwrap()
+            .neighborhood(Neighborhood::VON_NEUMANN)
+            .wrapping(true);
+        simulation.step();
+        assert_eq!(simulation.energy(), &parse(&["0212"]));
+
+        assert_eq!(
+            FlashSimulation::parse("").err().map(|e| e.to_string()),
+            Some("no octopuses".to_string())
+        );
+
+        // Energy stops at u32::MAX, still above the highest threshold there can be, while the octopus
+        // gives itself energy twice when it flashes
+        let mut simulation = FlashSimulation::new(Grid::from_fn(1, 1, |_| u32::MAX - 1))
+            .unwrap()
+            .threshold(u32::MAX - 1)
+            .neighborhood(Neighborhood::stencil(&[(0, 0), (0, 0)]));
+        assert_eq!(simulation.step().flashes, 1);
+        assert_eq!(simulation.energy(), &parse(&["0"]));
     }
 }
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use common::{Grid, Neighborhood, ParseError, Point2};
+
+/// What happened in one step of a `FlashSimulation`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct StepStats {
+    /// Steps taken so far, counting this one.
+    pub step: usize,
+    pub flashes: usize,
+    /// Whether every octopus flashed in this step.
+    pub synchronised: bool,
+}
+
+/// The first step that returned the grid to an earlier state, from then on the steps repeat.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Cycle {
+    /// Steps taken before the repeating part, 0 when the starting state is part of it.
+    pub start: usize,
+    pub length: usize,
+}
+
+/// Octopuses gaining energy every step. One whose energy goes above the `threshold` flashes, giving
+/// energy to its neighbours, and starts over at 0. The puzzle uses a threshold of 9 and the 8 neighbours
+/// within the grid.
+#[derive(Clone)]
+pub struct FlashSimulation {
+    energy: Grid<u32>,
+    threshold: u32,
+    neighborhood: Neighborhood<'static>,
+    wrapping: bool,
+    steps: usize,
+}
+
+impl FlashSimulation {
+    /// The octopuses with the `energy` levels, there has to be at least one.
+    pub fn new(energy: Grid<u32>) -> Result<FlashSimulation, ParseError> {
+        if energy.width() * energy.height() == 0 {
+            return Err(ParseError::new(1, "no octopuses"));
+        }
+
+        Ok(FlashSimulation {
+            energy,
+            threshold: 9,
+            neighborhood: Neighborhood::MOORE,
+            wrapping: false,
+            steps: 0,
+        })
+    }
+
+    /// The octopuses in `input`, one digit for the energy level of each.
+    pub fn parse(input: &str) -> Result<FlashSimulation, ParseError> {
+        FlashSimulation::new(Grid::parse(&input.lines().collect::<Vec<_>>(), |c| {
+            c.to_digit(10).ok_or(format!("Invalid digit '{}'", c))
+        })?)
+    }
+
+    /// Octopuses flash once their energy goes above `threshold`, which has to be below `u32::MAX` for
+    /// that to be possible at all.
+    pub fn threshold(mut self, threshold: u32) -> Self {
+        assert!(
+            threshold < u32::MAX,
+            "Energy never goes above a threshold of {}",
+            threshold
+        );
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn neighborhood(mut self, neighborhood: Neighborhood<'static>) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    /// Whether flashes at an edge reach the octopuses at the opposite edge.
+    pub fn wrapping(mut self, wrapping: bool) -> Self {
+        self.wrapping = wrapping;
+        self
+    }
+
+    pub fn energy(&self) -> &Grid<u32> {
+        &self.energy
+    }
+
+    pub fn step(&mut self) -> StepStats {
+        // Energy stops at u32::MAX, that is above any threshold so it flashes all the same
+        self.energy
+            .values_mut()
+            .for_each(|energy| *energy = energy.saturating_add(1));
+
+        let mut flashing = self
+            .energy
+            .iter()
+            .filter(|&(_, &energy)| energy > self.threshold)
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+        let mut flashed = Grid::from_fn(self.energy.width(), self.energy.height(), |_| false);
+        let bounds = self.energy.bounds();
+
+        // Every octopus flashes at most once, the moment its energy goes past the threshold
+        while let Some(position) = flashing.pop() {
+            if flashed[&position] {
+                continue;
+            }
+            flashed[&position] = true;
+
+            let mut charge = |neighbor: Point2<i64>| {
+                self.energy[&neighbor] = self.energy[&neighbor].saturating_add(1);
+
+                if self.energy[&neighbor] > self.threshold && !flashed[&neighbor] {
+                    flashing.push(neighbor);
+                }
+            };
+            // The two kinds of neighbours are iterators of their own, branching keeps both lazy
+            if self.wrapping {
+                self.neighborhood
+                    .wrapping(position, bounds)
+                    .for_each(&mut charge);
+            } else {
+                self.neighborhood
+                    .inside(position, bounds)
+                    .for_each(&mut charge);
+            }
+        }
+
+        let mut flashes = 0;
+        for (position, energy) in self.energy.iter_mut() {
+            if flashed[&position] {
+                *energy = 0;
+                flashes += 1;
+            }
+        }
+
+        self.steps += 1;
+        StepStats {
+            step: self.steps,
+            flashes,
+            synchronised: flashes == self.energy.width() * self.energy.height(),
+        }
+    }
+
+    /// Step after step, without end.
+    pub fn steps(&mut self) -> impl Iterator<Item = StepStats> + '_ {
+        std::iter::repeat_with(move || self.step())
+    }
+
+    /// Step on a copy until the states repeat, the simulation itself is left as it is.
+    pub fn cycle(&self) -> Cycle {
+        match self.clone().run_until(|_| false) {
+            Ok(_) => unreachable!(),
+            Err(cycle) => cycle,
+        }
+    }
+
+    /// The first step, counting from the current one, in which all octopuses flash. `None` when the
+    /// states start repeating before that ever happens.
+    pub fn first_synchronised(&self) -> Option<usize> {
+        self.clone().run_until(|stats| stats.synchronised).ok()
+    }
+
+    /// Step until `stop` holds for a step, giving the number of steps taken, or until the whole grid is in a
+    /// state it was in before, giving the cycle. There are only so many states, so this ends, but every
+    /// state up to the repeat is kept: memory grows with the number of steps that takes.
+    fn run_until<F>(&mut self, mut stop: F) -> Result<usize, Cycle>
+    where
+        F: FnMut(&StepStats) -> bool,
+    {
+        let first = self.steps;
+        let mut seen = HashMap::from([(self.energy.clone(), 0)]);
+
+        loop {
+            let stats = self.step();
+            let taken = stats.step - first;
+
+            if stop(&stats) {
+                return Ok(taken);
+            }
+            if let Some(start) = seen.insert(self.energy.clone(), taken) {
+                return Err(Cycle {
+                    start,
+                    length: taken - start,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use std::collections::{HashMap, HashSet};
+
+use common::{
+    parse_lines,
+    search::{walk_paths, PathWalk},
+    ParseError,
+};
+
+use crate::{
+    parse_edge,
+    policy::{Limit, VisitPolicy},
+    Edge,
+};
+
+/// The cave system with every cave numbered by the order it first appears in, and the caves it connects
+/// to listed by number. Building it rejects inputs that have no answer or infinitely many.
+pub struct CaveGraph {
+    names: Vec<String>,
+    small: Vec<bool>,
+    neighbors: Vec<Vec<usize>>,
+    start: usize,
+    end: usize,
+}
+
+impl CaveGraph {
+    /// The graph of `edges`, where edge `n` came from line `n + 1` of the input.
+    pub fn new(edges: &[Edge]) -> Result<CaveGraph, ParseError> {
+        let mut ids = HashMap::new();
+        let mut names = vec![];
+        let mut small = vec![];
+        let mut neighbors: Vec<Vec<usize>> = vec![];
+        // The line every connection was first made on, to point at duplicates
+        let mut lines = HashMap::new();
+
+        for (n, edge) in edges.iter().enumerate() {
+            let error = |message: String| Err(ParseError::new(1, message).on_line(n + 1));
+
+            let mut id = |name: &str, is_small: bool| {
+                *ids.entry(name.to_string()).or_insert_with(|| {
+                    names.push(name.to_string());
+                    small.push(is_small);
+                    neighbors.push(vec![]);
+                    names.len() - 1
+                })
+            };
+            let (from, to) = (
+                id(&edge.from.name, edge.from.is_small()),
+                id(&edge.to.name, edge.to.is_small()),
+            );
+
+            if from == to {
+                return error(format!("cave '{}' connects to itself", edge.from.name));
+            }
+            if !small[from] && !small[to] {
+                return error(format!(
+                    "big caves '{}' and '{}' are connected, there would be endless paths",
+                    edge.from.name, edge.to.name
+                ));
+            }
+            if let Some(first) = lines.insert((from.min(to), from.max(to)), n + 1) {
+                return error(format!(
+                    "duplicate edge '{}-{}', first on line {}",
+                    edge.from.name, edge.to.name, first
+                ));
+            }
+
+            neighbors[from].push(to);
+            neighbors[to].push(from);
+        }
+
+        let find = |name: &str| {
+            ids.get(name)
+                .copied()
+                .ok_or_else(|| ParseError::new(1, format!("no cave named '{}'", name)))
+        };
+
+        Ok(CaveGraph {
+            start: find("start")?,
+            end: find("end")?,
+            names,
+            small,
+            neighbors,
+        })
+    }
+
+    /// The graph of the edges in `input`, one "from-to" per line.
+    pub fn parse(input: &str) -> Result<CaveGraph, ParseError> {
+        CaveGraph::new(&parse_lines(input, parse_edge)?)
+    }
+
+    pub fn cave_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn name(&self, cave: usize) -> &str {
+        &self.names[cave]
+    }
+
+    pub fn is_small(&self, cave: usize) -> bool {
+        self.small[cave]
+    }
+
+    pub fn neighbors(&self, cave: usize) -> &[usize] {
+        &self.neighbors[cave]
+    }
+
+    /// The limit of every cave under `policy`, indexed by cave.
+    fn limits(&self, policy: &VisitPolicy) -> Vec<Limit> {
+        (0..self.names.len())
+            .map(|cave| {
+                if cave == self.start {
+                    Limit::Never
+                } else {
+                    policy.limit_of(&self.names[cave], self.small[cave])
+                }
+            })
+            .collect()
+    }
+
+    /// Every path from start to end allowed by `policy`, one at a time.
+    pub fn paths<'a>(&'a self, policy: &VisitPolicy) -> impl Iterator<Item = Vec<usize>> + 'a {
+        let limits = self.limits(policy);
+
+        walk_paths(
+            self.start,
+            CaveWalk {
+                graph: self,
+                entered: vec![0; limits.len()],
+                limits,
+                used: 0,
+                revisits: policy.revisit_count() as usize,
+            },
+        )
+    }
+
+    /// Number of paths `paths` would produce, without building any of them. When the entries of all
+    /// limited caves do not fit in 128 bits the paths are enumerated instead.
+    pub fn count_paths(&self, policy: &VisitPolicy) -> usize {
+        let limits = self.limits(policy);
+
+        // Where the entries of every limited cave sit in the state, wide enough to count up to its limit
+        let mut fields = vec![];
+        let mut offset = 0;
+        for limit in &limits {
+            let width = match limit {
+                Limit::Times(n) => u8::BITS - n.leading_zeros(),
+                Limit::Unlimited | Limit::Never => 0,
+            };
+            fields.push((offset, width));
+            offset += width;
+        }
+        if offset > u128::BITS {
+            return self.paths(policy).count();
+        }
+
+        self.count(
+            self.start,
+            &limits,
+            &fields,
+            0,
+            policy.revisit_count(),
+            &mut HashMap::new(),
+        )
+    }
+
+    /// Paths from `cave` to the end given the `state`, how often every limited cave was entered, and the
+    /// revisits left. The count only depends on these, so each combination is counted once. Entries stop
+    /// being counted at the limit, after that every entry takes a revisit however many there were.
+    fn count(
+        &self,
+        cave: usize,
+        limits: &[Limit],
+        fields: &[(u32, u32)],
+        state: u128,
+        revisits: u8,
+        memo: &mut HashMap<(usize, u128, u8), usize>,
+    ) -> usize {
+        if cave == self.end {
+            return 1;
+        }
+        if let Some(&count) = memo.get(&(cave, state, revisits)) {
+            return count;
+        }
+
+        let mut count = 0;
+        for &next in &self.neighbors[cave] {
+            let (offset, width) = fields[next];
+            let entered = (state >> offset) & ((1 << width) - 1);
+
+            let (state, revisits) = match limits[next] {
+                Limit::Unlimited => (state, revisits),
+                Limit::Times(n) if entered < n as u128 => (state + (1 << offset), revisits),
+                Limit::Times(_) if revisits > 0 => (state, revisits - 1),
+                Limit::Times(_) | Limit::Never => continue,
+            };
+            count += self.count(next, limits, fields, state, revisits, memo);
+        }
+
+        memo.insert((cave, state, revisits), count);
+        count
+    }
+
+    /// The cave system in Graphviz DOT. Small caves are ellipses and big caves boxes, start and end are
+    /// drawn twice outlined. The caves and passages of `path`, if given, are highlighted.
+    pub fn to_dot(&self, path: Option<&[usize]>) -> String {
+        let path = path.unwrap_or_default();
+        let passages = path
+            .windows(2)
+            .map(|w| (w[0].min(w[1]), w[0].max(w[1])))
+            .collect::<HashSet<_>>();
+
+        let mut dot = String::from("graph caves {\n");
+
+        for (cave, name) in self.names.iter().enumerate() {
+            let mut attributes = vec![if self.small[cave] {
+                "shape=ellipse"
+            } else {
+                "shape=box, style=bold"
+            }];
+            if cave == self.start || cave == self.end {
+                attributes.push("peripheries=2");
+            }
+            if path.contains(&cave) {
+                attributes.push("color=red, fontcolor=red");
+            }
+            dot += &format!("    \"{}\" [{}];\n", name, attributes.join(", "));
+        }
+
+        for (cave, neighbors) in self.neighbors.iter().enumerate() {
+            for &neighbor in neighbors.iter().filter(|&&neighbor| cave < neighbor) {
+                dot += &format!(
+                    "    \"{}\" -- \"{}\"",
+                    self.names[cave], self.names[neighbor]
+                );
+                if passages.contains(&(cave, neighbor)) {
+                    dot += " [color=red, penwidth=2]";
+                }
+                dot += ";\n";
+            }
+        }
+
+        dot + "}\n"
+    }
+}
+
+/// The caves entered on the current path, for `CaveGraph::paths`.
+struct CaveWalk<'a> {
+    graph: &'a CaveGraph,
+    limits: Vec<Limit>,
+    entered: Vec<usize>,
+    // Every entry beyond a limit used up one revisit
+    used: usize,
+    revisits: usize,
+}
+
+impl CaveWalk<'_> {
+    fn at_limit(&self, cave: usize) -> bool {
+        matches!(self.limits[cave], Limit::Times(n) if self.entered[cave] >= n as usize)
+    }
+}
+
+impl PathWalk<usize> for CaveWalk<'_> {
+    fn neighbors(&self, cave: &usize) -> &[usize] {
+        &self.graph.neighbors[*cave]
+    }
+
+    fn allows(&self, cave: &usize) -> bool {
+        match self.limits[*cave] {
+            Limit::Unlimited => true,
+            Limit::Times(_) => !self.at_limit(*cave) || self.used < self.revisits,
+            Limit::Never => false,
+        }
+    }
+
+    fn is_goal(&self, cave: &usize) -> bool {
+        *cave == self.graph.end
+    }
+
+    fn enter(&mut self, cave: &usize) {
+        if self.at_limit(*cave) {
+            self.used += 1;
+        }
+        self.entered[*cave] += 1;
+    }
+
+    fn leave(&mut self, cave: &usize) {
+        self.entered[*cave] -= 1;
+        if self.at_limit(*cave) {
+            self.used -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CaveGraph;
+    use crate::VisitPolicy;
+
+    fn graph(input: &str) -> Result<CaveGraph, String> {
+        CaveGraph::parse(input).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn graph_test1() {
+        let caves = graph("start-A\nA-b\nb-end\nA-end").unwrap();
+
+        assert_eq!(caves.cave_count(), 4);
+        assert_eq!(caves.name(caves.start()), "start");
+        assert!(!caves.is_small(1) && caves.is_small(2));
+        assert_eq!(caves.neighbors(1), &[0, 2, 3]);
+
+        assert_eq!(
+            graph("start-A\nA-B\nA-end").err(),
+            Some("2:1: big caves 'A' and 'B' are connected, there would be endless paths".into())
+        );
+        assert_eq!(
+            graph("start-a\na-end\nend-a").err(),
+            Some("3:1: duplicate edge 'end-a', first on line 2".into())
+        );
+        assert_eq!(
+            graph("start-a\na-a").err(),
+            Some("2:1: cave 'a' connects to itself".into())
+        );
+    }
+
+    #[test]
+    fn graph_test2() {
+        // More small caves than bits in a u64, a single chain from start to end
+        let name = |n: u8| format!("{}{}", (b'a' + n / 26) as char, (b'a' + n % 26) as char);
+        let input = (0..70)
+            .map(|n| format!("{}-{}", name(n), name(n + 1)))
+            .chain([format!("start-{}", name(0)), format!("{}-end", name(70))])
+            .collect::<Vec<_>>()
+            .join("\n");
+        let caves = graph(&input).unwrap();
+
+        assert_eq!(caves.cave_count(), 73);
+        assert_eq!(caves.count_paths(&VisitPolicy::default()), 1);
+        assert_eq!(caves.count_paths(&VisitPolicy::default().revisits(1)), 1);
+        assert_eq!(caves.paths(&VisitPolicy::default()).count(), 1);
+
+        // Too many small caves to count in 128 bits, so the paths are enumerated, none of them reachable
+        let input = (0..140)
+            .map(|n| format!("{}-{}", name(n), name(n + 1)))
+            .chain(["start-end".to_string()])
+            .collect::<Vec<_>>()
+            .join("\n");
+        let caves = graph(&input).unwrap();
+        assert_eq!(caves.count_paths(&VisitPolicy::default().revisits(1)), 1);
+    }
+
+    #[test]
+    fn policy_test1() {
+        let caves = graph("start-A\nstart-b\nA-c\nA-b\nb-d\nA-end\nb-end").unwrap();
+
+        let policies = [
+            VisitPolicy::default(),
+            VisitPolicy::default().revisits(1),
+            VisitPolicy::default().revisits(2),
+            VisitPolicy::default().small_caves(2),
+            VisitPolicy::default().limit("A", 2).limit("c", 0),
+            VisitPolicy::default().forbid("A").revisits(3),
+        ];
+        let counts = policies
+            .iter()
+            .map(|policy| caves.count_paths(policy))
+            .collect::<Vec<_>>();
+
+        assert_eq!(&counts[..2], &[10, 36]);
+        // start,b,end then b and d again: start,b,d,b,end and start,b,d,b,d,b,end
+        assert_eq!(counts[5], 3);
+        for (policy, count) in policies.iter().zip(counts) {
+            assert_eq!(caves.paths(policy).count(), count, "{:?}", policy);
+        }
+
+        // Counts go past the largest limit without overflowing, b is entered 0 to 256 times
+        let caves = graph("start-A\nA-b\nA-end").unwrap();
+        let policy = VisitPolicy::default().limit("b", u8::MAX).revisits(1);
+        assert_eq!(caves.count_paths(&policy), 257);
+        assert_eq!(caves.paths(&policy).count(), 257);
+        assert_eq!(
+            graph("start-a\na-b").err(),
+            Some("no cave named 'end'".into())
+        );
+    }
+
+    #[test]
+    fn dot_test1() {
+        let caves = graph("start-A\nA-b\nA-end").unwrap();
+        let path = caves.paths(&VisitPolicy::default()).last().unwrap();
+
+        assert_eq!(
+            caves.to_dot(Some(&path)),
+            [
+                "graph caves {",
+                "    \"start\" [shape=ellipse, peripheries=2, color=red, fontcolor=red];",
+                "    \"A\" [shape=box, style=bold, color=red, fontcolor=red];",
+                "    \"b\" [shape=ellipse];",
+                "    \"end\" [shape=ellipse, peripheries=2, color=red, fontcolor=red];",
+                "    \"start\" -- \"A\" [color=red, penwidth=2];",
+                "    \"A\" -- \"b\";",
+                "    \"A\" -- \"end\" [color=red, penwidth=2];",
+                "}",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
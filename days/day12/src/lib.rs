@@ -1,14 +1,17 @@
-use std::collections::HashSet;
+mod graph;
+mod policy;
 
-use common::dfs_all;
+use common::{Answer, ParseError, Registry, Solution};
+pub use graph::CaveGraph;
+pub use policy::{Limit, VisitPolicy};
 
 #[derive(PartialEq, Debug, Clone, Eq, Hash)]
-struct Cave {
+pub struct Cave {
     pub name: String,
 }
 
 #[derive(PartialEq, Debug, Clone, Eq, Hash)]
-struct Edge {
+pub struct Edge {
     pub from: Cave,
     pub to: Cave,
 }
@@ -18,12 +21,6 @@ impl Cave {
         self.name.chars().all(|c| c.is_ascii_lowercase())
     }
 
-    pub fn start() -> Cave {
-        Cave {
-            name: "start".to_string(),
-        }
-    }
-
     // pub fn end() -> Cave {
     //     Cave {
     //         name: "start".to_string(),
@@ -38,157 +35,68 @@ impl Cave {
     }
 }
 
-fn parse_edge(line: &str) -> Edge {
+pub fn parse_edge(line: &str) -> Result<Edge, ParseError> {
     let (from, to) = line
         .trim()
         .split_once('-')
-        .expect("invalid edge: expected exactly one '-'");
+        .ok_or_else(|| ParseError::new(1, "invalid edge: expected exactly one '-'"))?;
+
+    for name in [from, to] {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(ParseError::at(
+                line,
+                name,
+                format!("invalid cave name '{}'", name),
+            ));
+        }
+    }
 
-    Edge {
+    Ok(Edge {
         from: Cave {
             name: from.to_string(),
         },
         to: Cave {
             name: to.to_string(),
         },
-    }
-}
-
-fn paths_from_edges<H>(edges: &[Edge], cond: &H) -> Vec<Vec<Cave>>
-where
-    H: Fn(&Cave, &Vec<Cave>) -> bool,
-{
-    let mut visited = vec![];
-    let mut paths = vec![];
-
-    dfs_all(
-        &Cave::start(),
-        &|current| current.is_end(),
-        &|current, visited| {
-            //print!("{} --> ", current.name);
-
-            let n = edges
-                .iter()
-                .filter_map(|edge| {
-                    if current == &edge.from {
-                        if cond(&edge.to, visited) {
-                            Some(edge.to.clone())
-                        } else {
-                            None
-                        }
-                    } else if current == &edge.to {
-                        if cond(&edge.from, visited) {
-                            Some(edge.from.clone())
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                })
-                //.inspect(|cave| print!("{} ", cave.name))
-                .collect::<Vec<_>>();
-
-            //println!();
-
-            n
-        },
-        &mut visited,
-        &mut paths,
-    );
-
-    paths
-}
-
-fn small_caves(edges: &[Edge]) -> HashSet<Cave> {
-    edges
-        .iter()
-        .flat_map(|edge| {
-            let mut r = vec![];
-
-            if edge.from.is_small() {
-                r.push(edge.from.clone());
-            }
-            if edge.to.is_small() {
-                r.push(edge.to.clone());
-            }
-            r
-        })
-        .collect::<HashSet<_>>()
-}
-
-fn is_valid_target_cave_part1(cave: &Cave, visited: &[Cave]) -> bool {
-    let c = visited.iter().filter(|c| c == &cave).count();
-
-    !cave.is_start() && (cave.is_end() || !cave.is_small() || c == 0)
-}
-
-fn is_valid_target_cave_part2(cave: &Cave, visited: &[Cave], twice: &Cave) -> bool {
-    let c = visited.iter().filter(|c| c == &cave).count();
-
-    !cave.is_start() && (cave.is_end() || !cave.is_small() || c == 0 || c == 1 && cave == twice)
+    })
 }
 
-static INPUT: &[&str] = &[
-    "bm-XY", "ol-JS", "bm-im", "RD-ol", "bm-QI", "JS-ja", "im-gq", "end-im", "ja-ol", "JS-gq",
-    "bm-AF", "RD-start", "RD-ja", "start-ol", "cj-bm", "start-JS", "AF-ol", "end-QI", "QI-gq",
-    "ja-gq", "end-AF", "im-QI", "bm-gq", "ja-QI", "gq-RD",
-];
-
-fn part1() {
-    let edges = INPUT
-        .iter()
-        .map(|line| parse_edge(line))
-        .collect::<Vec<_>>();
-
-    let solutions = paths_from_edges(&edges, &|cave, visited| {
-        is_valid_target_cave_part1(cave, visited)
-    });
-    assert_eq!(solutions.len(), 3887);
-
-    println!("Day 12 part 1 : Number of paths is {}", solutions.len());
+struct PassagePathing {
+    caves: CaveGraph,
 }
 
-fn part2() {
-    let edges = INPUT
-        .iter()
-        .map(|line| parse_edge(line))
-        .collect::<Vec<_>>();
-
-    let solutions = small_caves(&edges)
-        .iter()
-        .flat_map(|twice| {
-            paths_from_edges(&edges, &|cave, visited| {
-                is_valid_target_cave_part2(cave, visited, twice)
-            })
+impl Solution for PassagePathing {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(PassagePathing {
+            caves: CaveGraph::parse(input)?,
         })
-        .collect::<HashSet<_>>();
+    }
 
-    assert_eq!(solutions.len(), 104834);
+    fn part1(&self) -> Answer {
+        self.caves.count_paths(&VisitPolicy::default()).into()
+    }
 
-    println!("Day 12 part 2 : Number of paths is {}", solutions.len());
+    fn part2(&self) -> Answer {
+        self.caves
+            .count_paths(&VisitPolicy::default().revisits(1))
+            .into()
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<PassagePathing>(12, "Passage Pathing", env!("CARGO_MANIFEST_DIR"));
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
-
-    use crate::{
-        is_valid_target_cave_part1, is_valid_target_cave_part2, parse_edge, paths_from_edges,
-        small_caves, Cave,
-    };
+    use crate::{parse_edge, CaveGraph, VisitPolicy};
 
     static TEST_INPUT1: &[&str] = &["start-A", "start-b", "A-c", "A-b", "b-d", "A-end", "b-end"];
     #[test]
     fn part1_test1() {
         let moves = TEST_INPUT1
             .iter()
-            .map(|line| parse_edge(line))
+            .map(|line| parse_edge(line).unwrap())
             .collect::<Vec<_>>();
 
         assert_eq!(moves.len(), 7);
@@ -205,16 +113,21 @@ mod tests {
     fn part1_test2() {
         let edges = TEST_INPUT1
             .iter()
-            .map(|line| parse_edge(line))
+            .map(|line| parse_edge(line).unwrap())
             .collect::<Vec<_>>();
+        let caves = CaveGraph::new(&edges).unwrap();
 
-        let solutions = paths_from_edges(&edges, &|cave, visited| {
-            is_valid_target_cave_part1(cave, visited)
-        });
+        let solutions = caves.paths(&VisitPolicy::default()).collect::<Vec<_>>();
 
-        println!("{:?}", solutions);
+        for path in &solutions {
+            println!(
+                "{:?}",
+                path.iter().map(|&c| caves.name(c)).collect::<Vec<_>>()
+            );
+        }
 
         assert_eq!(solutions.len(), 10);
+        assert_eq!(caves.count_paths(&VisitPolicy::default()), 10);
     }
 
     static TEST_INPUT2: &[&str] = &[
@@ -226,14 +139,13 @@ mod tests {
     fn part1_test3() {
         let edges = TEST_INPUT2
             .iter()
-            .map(|line| parse_edge(line))
+            .map(|line| parse_edge(line).unwrap())
             .collect::<Vec<_>>();
+        let caves = CaveGraph::new(&edges).unwrap();
 
-        let solutions = paths_from_edges(&edges, &|cave, visited| {
-            is_valid_target_cave_part1(cave, visited)
-        });
-
-        assert_eq!(solutions.len(), 19);
+        assert_eq!(caves.paths(&VisitPolicy::default()).count(), 19);
+        assert_eq!(caves.count_paths(&VisitPolicy::default()), 19);
+        assert_eq!(caves.count_paths(&VisitPolicy::default().revisits(1)), 103);
     }
 
     static TEST_INPUT3: &[&str] = &[
@@ -245,34 +157,35 @@ mod tests {
     fn part1_test4() {
         let edges = TEST_INPUT3
             .iter()
-            .map(|line| parse_edge(line))
+            .map(|line| parse_edge(line).unwrap())
             .collect::<Vec<_>>();
+        let caves = CaveGraph::new(&edges).unwrap();
 
-        let solutions = paths_from_edges(&edges, &|cave, visited| {
-            is_valid_target_cave_part1(cave, visited)
-        });
-
-        assert_eq!(solutions.len(), 226);
+        assert_eq!(caves.paths(&VisitPolicy::default()).count(), 226);
+        assert_eq!(caves.count_paths(&VisitPolicy::default()), 226);
+        assert_eq!(caves.count_paths(&VisitPolicy::default().revisits(1)), 3509);
     }
 
     #[test]
     fn part2_test1() {
         let edges = TEST_INPUT1
             .iter()
-            .map(|line| parse_edge(line))
+            .map(|line| parse_edge(line).unwrap())
             .collect::<Vec<_>>();
+        let caves = CaveGraph::new(&edges).unwrap();
 
-        let solutions = small_caves(&edges)
-            .iter()
-            .flat_map(|twice| {
-                paths_from_edges(&edges, &|cave, visited| {
-                    is_valid_target_cave_part2(cave, visited, twice)
-                })
-            })
-            .collect::<HashSet<_>>();
+        let solutions = caves
+            .paths(&VisitPolicy::default().revisits(1))
+            .collect::<Vec<_>>();
 
-        println!("{:?}", solutions);
+        for path in &solutions {
+            println!(
+                "{:?}",
+                path.iter().map(|&c| caves.name(c)).collect::<Vec<_>>()
+            );
+        }
 
         assert_eq!(solutions.len(), 36);
+        assert_eq!(caves.count_paths(&VisitPolicy::default().revisits(1)), 36);
     }
 }
@@ -0,0 +1,76 @@
+use std::collections::{HashMap, HashSet};
+
+/// How often a path may enter each cave. Small caves may be entered `small` times and big caves as often
+/// as they like, unless a cave has a limit of its own. On top of that a path has `revisits` extra
+/// entries to spend on caves that reached their limit. Forbidden caves, and the start, are never entered.
+///
+/// Part 1 is the default policy, part 2 is `VisitPolicy::default().revisits(1)`.
+#[derive(Clone, Debug)]
+pub struct VisitPolicy {
+    small: u8,
+    revisits: u8,
+    limits: HashMap<String, u8>,
+    forbidden: HashSet<String>,
+}
+
+impl Default for VisitPolicy {
+    fn default() -> Self {
+        VisitPolicy {
+            small: 1,
+            revisits: 0,
+            limits: HashMap::new(),
+            forbidden: HashSet::new(),
+        }
+    }
+}
+
+/// How often one cave may be entered, as decided by a `VisitPolicy`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Limit {
+    Unlimited,
+    Times(u8),
+    Never,
+}
+
+impl VisitPolicy {
+    /// Every small cave may be entered up to `times` times.
+    pub fn small_caves(mut self, times: u8) -> Self {
+        self.small = times;
+        self
+    }
+
+    /// A path may go over the limit of a cave this many times in total.
+    pub fn revisits(mut self, revisits: u8) -> Self {
+        self.revisits = revisits;
+        self
+    }
+
+    /// The cave named `name` may be entered up to `times` times, whether small or big.
+    pub fn limit(mut self, name: &str, times: u8) -> Self {
+        self.limits.insert(name.to_string(), times);
+        self
+    }
+
+    /// The cave named `name` is never entered, revisits do not change that.
+    pub fn forbid(mut self, name: &str) -> Self {
+        self.forbidden.insert(name.to_string());
+        self
+    }
+
+    pub fn revisit_count(&self) -> u8 {
+        self.revisits
+    }
+
+    /// The limit for a cave that is not the start.
+    pub fn limit_of(&self, name: &str, small: bool) -> Limit {
+        if self.forbidden.contains(name) {
+            Limit::Never
+        } else if let Some(&times) = self.limits.get(name) {
+            Limit::Times(times)
+        } else if small {
+            Limit::Times(self.small)
+        } else {
+            Limit::Unlimited
+        }
+    }
+}
@@ -1,102 +1,93 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{fmt, iter, str::FromStr};
 
-use common::Coordinate;
+use common::{
+    animation::{Cell, GraphicRendition},
+    Answer, Bounds, Grid, ParseError, Point2, Registry, Solution,
+};
 use regex::Regex;
 
+#[derive(Clone)]
 struct Map {
-    fields: HashMap<Coordinate<i32>, i32>,
+    paper: Grid<bool>,
 }
 
+/// Largest width and height of the paper, the whole sheet is kept in memory.
+const MAX_SIDE: usize = 1 << 14;
+
 impl Map {
-    pub fn parse(dots: &[&str]) -> Map {
-        let mut fields = HashMap::new();
-
-        for line in dots {
-            let mut s = line.split(",");
-            fields.insert(
-                Coordinate::new(
-                    s.next()
-                        .expect("Error parsing field : x")
-                        .parse::<i32>()
-                        .unwrap(),
-                    s.next()
-                        .expect("Error parsing field : y")
-                        .parse::<i32>()
-                        .unwrap(),
-                    0,
-                ),
-                1,
-            );
+    pub fn parse(dots: &[&str]) -> Result<Map, ParseError> {
+        let dots = dots
+            .iter()
+            .enumerate()
+            .map(|(n, line)| {
+                let dot = line
+                    .parse::<Point2<usize>>()
+                    .map_err(|e| e.on_line(n + 1))?;
+
+                for (i, value) in [dot.x, dot.y].into_iter().enumerate() {
+                    if value >= MAX_SIDE {
+                        let token = line.split(',').nth(i).unwrap_or(line).trim();
+                        return Err(ParseError::at(
+                            line,
+                            token,
+                            format!(
+                                "Dot at {} is beyond the largest paper of {}",
+                                value, MAX_SIDE
+                            ),
+                        )
+                        .on_line(n + 1));
+                    }
+                }
+                Ok(dot)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let Some(corner) = dots.iter().copied().collect::<Bounds<_>>().max() else {
+            return Err(ParseError::new(1, "No dots found").on_line(1));
+        };
+
+        let mut paper = Grid::from_fn(corner.x + 1, corner.y + 1, |_| false);
+
+        for dot in &dots {
+            paper[&Point2::new(dot.x as i64, dot.y as i64)] = true;
         }
 
-        Map { fields }
+        Ok(Map { paper })
     }
 
     pub fn fold(&mut self, fold: &Fold) {
-        match fold.axis {
-            Axis::X => self.fold_vertical(fold.offset),
-            Axis::Y => self.fold_horizontal(fold.offset),
-        }
-    }
-
-    fn fold_horizontal(&mut self, offset: i32) {
-        let (x_min, x_max, y_min, _) = self.extends();
-
-        for y in y_min..offset {
-            for x in x_min..=x_max {
-                let folded = self
-                    .fields
-                    .entry(Coordinate::new(x, 2 * offset - y, 0))
-                    .or_insert(0);
-
-                *self.fields.entry(Coordinate::new(x, y, 0)).or_insert(0) |= *folded;
-            }
-        }
-
-        self.fields.retain(|e, _| e.y < offset);
-    }
-
-    fn fold_vertical(&mut self, offset: i32) {
-        let (x_min, _, y_min, y_max) = self.extends();
-
-        for y in y_min..=y_max {
-            for x in x_min..offset {
-                let folded = self
-                    .fields
-                    .entry(Coordinate::new(2 * offset - x, y, 0))
-                    .or_insert(0);
-
-                *self.fields.entry(Coordinate::new(x, y, 0)).or_insert(0) |= *folded;
-            }
-        }
-
-        self.fields.retain(|e, _| e.x < offset);
+        let offset = fold.offset as i64;
+
+        let (width, height) = match fold.axis {
+            Axis::X => (fold.offset, self.paper.height()),
+            Axis::Y => (self.paper.width(), fold.offset),
+        };
+
+        // Every dot ends up at the spot it mirrors to, dots beyond the far edge are simply not there
+        self.paper = Grid::from_fn(width, height, |c| {
+            let mirrored = match fold.axis {
+                Axis::X => Point2::new(2 * offset - c.x, c.y),
+                Axis::Y => Point2::new(c.x, 2 * offset - c.y),
+            };
+
+            self.paper.get(&c) == Some(&true) || self.paper.get(&mirrored) == Some(&true)
+        });
     }
 
-    fn extends(&self) -> (i32, i32, i32, i32) {
-        let x_min = self.fields.keys().min_by_key(|c| c.x).expect("Eeps").x;
-        let x_max = self.fields.keys().max_by_key(|c| c.x).expect("Eeps").x;
-        let y_min = self.fields.keys().min_by_key(|c| c.y).expect("Eeps").y;
-        let y_max = self.fields.keys().max_by_key(|c| c.y).expect("Eeps").y;
-        (x_min, x_max, y_min, y_max)
+    pub fn dots(&self) -> usize {
+        self.paper.values().filter(|&&dot| dot).count()
     }
 
+    #[allow(dead_code)]
     fn print(&self) {
-        let (x_min, x_max, y_min, y_max) = self.extends();
-
         println!();
-        for y in y_min..=y_max {
-            for x in x_min..=x_max {
-                let c = self
-                    .fields
-                    .get(&Coordinate { x, y, z: 0 })
-                    .or(Some(&0))
-                    .expect("Eeps");
-
-                print!("{}", if c == &1 { '#' } else { '.' });
-            }
-            println!();
-        }
+        print!("{}", self);
+    }
+}
+
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.paper.map(|&dot| if dot { '#' } else { '.' }))
     }
 }
 
@@ -123,94 +114,156 @@ impl FromStr for Axis {
 
 struct Fold {
     axis: Axis,
-    offset: i32,
+    offset: usize,
 }
 
-fn parse_folds(folds: &[&str]) -> Vec<Fold> {
+fn parse_folds(folds: &[&str]) -> Result<Vec<Fold>, ParseError> {
     let mut result = vec![];
-    let re = Regex::new(r"fold along (?<axis>x|y)=(?<offset>\d+)").unwrap();
-
-    for fold in folds {
-        if let Some(results) = re.captures(fold) {
-            let axis = &results["axis"]
-                .parse::<Axis>()
-                .unwrap_or_else(|_| panic!("Invalid fold axis {}", &results["axis"].to_string()));
-
-            let offset = &results["offset"]
-                .parse::<i32>()
-                .unwrap_or_else(|_| panic!("Invalid fold offset {}", &results["offset"].to_string()));
-
-            result.push(Fold {
-                axis: axis.clone(),
-                offset: *offset,
-            });
-        }
+    let re = Regex::new(r"^fold along (?<axis>x|y)=(?<offset>\d+)$").unwrap();
+
+    for (n, fold) in folds.iter().enumerate() {
+        let results = re.captures(fold).ok_or_else(|| {
+            ParseError::new(1, "Invalid fold, expected 'fold along <x|y>=<offset>'").on_line(n + 1)
+        })?;
+
+        let axis = results["axis"].parse::<Axis>().map_err(|_| {
+            ParseError::at(
+                fold,
+                &results["axis"],
+                format!("Invalid fold axis {}", &results["axis"]),
+            )
+            .on_line(n + 1)
+        })?;
+
+        let offset = results["offset"].parse::<usize>().map_err(|_| {
+            ParseError::at(
+                fold,
+                &results["offset"],
+                format!("Invalid fold offset {}", &results["offset"]),
+            )
+            .on_line(n + 1)
+        })?;
+
+        result.push(Fold { axis, offset });
     }
 
-    result
+    Ok(result)
 }
 
-fn parse_dots_and_folds<'a>(input: &'a [&'a str]) -> (&'a [&'a str], &'a [&'a str]) {
+fn parse_dots_and_folds<'a>(
+    input: &'a [&'a str],
+) -> Result<(&'a [&'a str], &'a [&'a str]), ParseError> {
     let end_of_dots = input
         .iter()
         .enumerate()
         .find(|(_, &s)| s.is_empty())
-        .expect("No empty line found in instructions")
+        .ok_or_else(|| ParseError::new(1, "No empty line found in instructions"))?
         .0;
     let (dots, folds) = input.split_at(end_of_dots);
-    let folds: &[&str] = folds
-        .split_first()
-        .expect("Failed to remove first empty line from folds")
-        .1;
-    (dots, folds)
+
+    // Skip the empty line between dots and folds
+    Ok((dots, &folds[1..]))
 }
 
-fn part1() {
-    let input = common::import("days/day13/input.txt");
-    let b = input.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+pub struct TransparentOrigami {
+    map: Map,
+    folds: Vec<Fold>,
+}
 
-    let (dots, folds) = parse_dots_and_folds(&b);
+impl TransparentOrigami {
+    /// The paper before folding and after every fold, dots are `true`.
+    pub fn papers(&self) -> impl Iterator<Item = Grid<bool>> + '_ {
+        let mut map = self.map.clone();
 
-    let mut map = Map::parse(dots);
-    let folds = parse_folds(folds);
+        iter::once(map.paper.clone()).chain(self.folds.iter().map(move |fold| {
+            map.fold(fold);
+            map.paper.clone()
+        }))
+    }
+}
 
-    map.fold(&folds[0]);
+/// Dots in bright white on an empty paper.
+pub fn dot_cell(dot: &bool) -> Cell {
+    const DOT: &[GraphicRendition] = &[
+        GraphicRendition::HighIntensity,
+        GraphicRendition::WhiteForeground,
+    ];
 
-    println!(
-        "Day 13 part 1 : Visible dots {}",
-        map.fields.values().filter(|&v| v != &0).count()
-    );
+    if *dot {
+        Cell::new('#', DOT)
+    } else {
+        Cell::plain('.')
+    }
 }
 
-fn part2() {
-    let input = common::import("days/day13/input.txt");
-    let b = input.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+impl Solution for TransparentOrigami {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let b = input.lines().collect::<Vec<_>>();
+
+        let (dots, folds) = parse_dots_and_folds(&b)?;
+        // Fold lines are numbered from the line after the empty one
+        let first_fold = dots.len() + 2;
+
+        let map = Map::parse(dots)?;
+        let parsed = parse_folds(folds).map_err(|e| {
+            let line = e.line + first_fold - 1;
+            e.on_line(line)
+        })?;
+
+        if parsed.is_empty() {
+            return Err(ParseError::new(1, "No folds found after the dots").on_line(first_fold - 1));
+        }
+
+        // Every fold has to be within what is left of the paper, otherwise it would grow
+        let (mut width, mut height) = (map.paper.width(), map.paper.height());
+        for (n, (fold, line)) in parsed.iter().zip(folds).enumerate() {
+            let size = match fold.axis {
+                Axis::X => &mut width,
+                Axis::Y => &mut height,
+            };
+            if fold.offset >= *size {
+                let offset = &line[line.rfind('=').unwrap() + 1..];
+                return Err(ParseError::at(
+                    line,
+                    offset,
+                    format!("Fold at {} is beyond the paper of {}", fold.offset, size),
+                )
+                .on_line(first_fold + n));
+            }
+            *size = fold.offset;
+        }
+
+        Ok(TransparentOrigami { map, folds: parsed })
+    }
 
-    let (dots, folds) = parse_dots_and_folds(&b);
+    fn part1(&self) -> Answer {
+        let mut map = self.map.clone();
 
-    let mut map = Map::parse(dots);
-    let folds = parse_folds(folds);
+        map.fold(&self.folds[0]);
 
-    for fold in folds {
-        map.fold(&fold);
+        map.dots().into()
     }
 
-    map.print();
+    fn part2(&self) -> Answer {
+        let mut map = self.map.clone();
+
+        for fold in &self.folds {
+            map.fold(fold);
+        }
 
-    println!(
-        "Day 13 part 2 : Visible dots {}",
-        map.fields.values().filter(|&v| v != &0).count()
-    );
+        map.to_string().trim_end().into()
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<TransparentOrigami>(13, "Transparent Origami", env!("CARGO_MANIFEST_DIR"));
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{parse_dots_and_folds, parse_folds, Axis, Map};
+    use common::Solution;
+
+    use crate::{parse_dots_and_folds, parse_folds, Axis, Map, TransparentOrigami};
 
     static TEST_INPUT1: &[&str] = &[
         "6,10",
@@ -238,14 +291,14 @@ mod tests {
 
     #[test]
     fn part1_test1() {
-        let (dots, folds) = parse_dots_and_folds(TEST_INPUT1);
+        let (dots, folds) = parse_dots_and_folds(TEST_INPUT1).unwrap();
 
         assert_eq!(dots.len(), 18);
         assert_eq!(folds.len(), 2);
 
-        let mut map = Map::parse(dots);
+        let mut map = Map::parse(dots).unwrap();
 
-        let folds = parse_folds(folds);
+        let folds = parse_folds(folds).unwrap();
         assert_eq!(folds.len(), 2);
         assert_eq!(folds[0].axis, Axis::Y);
         assert_eq!(folds[0].offset, 7);
@@ -256,11 +309,61 @@ mod tests {
         map.fold(&folds[0]);
         map.print();
 
-        assert_eq!(map.fields.values().filter(|&v| v != &0).count(), 17);
+        assert_eq!(map.dots(), 17);
 
         map.fold(&folds[1]);
         map.print();
 
-        assert_eq!(map.fields.len(), 5 * 7);
+        assert_eq!((map.paper.width(), map.paper.height()), (5, 7));
+    }
+
+    #[test]
+    fn papers_test1() {
+        let origami = TransparentOrigami::parse(&TEST_INPUT1.join("\n")).unwrap();
+
+        let papers = origami.papers().collect::<Vec<_>>();
+        assert_eq!(
+            papers
+                .iter()
+                .map(|paper| (paper.width(), paper.height()))
+                .collect::<Vec<_>>(),
+            vec![(11, 15), (11, 7), (5, 7)]
+        );
+        assert_eq!(papers[1].values().filter(|&&dot| dot).count(), 17);
+    }
+
+    #[test]
+    fn parse_test1() {
+        let parse = |input: &[&str]| {
+            TransparentOrigami::parse(&input.join("\n"))
+                .err()
+                .map(|e| e.to_string())
+        };
+
+        assert_eq!(
+            parse(&["1,1", "3,4", "", ""]),
+            Some("3:1: No folds found after the dots".into())
+        );
+        assert_eq!(
+            parse(&["1,1", "3,4", "", "fold along y=5"]),
+            Some("4:14: Fold at 5 is beyond the paper of 5".into())
+        );
+        // The second fold goes by the paper the first one left
+        assert_eq!(
+            parse(&["1,1", "3,4", "", "fold along x=2", "fold along x=2"]),
+            Some("5:14: Fold at 2 is beyond the paper of 2".into())
+        );
+        assert_eq!(
+            parse(&["1,1", "3,4", "", "fold along x=2", "fold along y=2"]),
+            None
+        );
+        assert_eq!(
+            parse(&["1,1", "3,18446744073709551615", "", "fold along x=2"]),
+            Some("2:3: Dot at 18446744073709551615 is beyond the largest paper of 16384".into())
+        );
+        assert_eq!(
+            parse(&["16384,1", "", "fold along x=2"]),
+            Some("1:1: Dot at 16384 is beyond the largest paper of 16384".into())
+        );
     }
 }
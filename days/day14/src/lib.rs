@@ -1,18 +1,36 @@
 use std::{collections::HashMap, str::FromStr};
 
+use common::{Answer, ParseError, Registry, Solution};
+
 #[derive(Debug)]
 struct Rule {
     from: String,
     to: String,
 }
 
-struct RuleParseError {}
-
 impl FromStr for Rule {
-    type Err = RuleParseError;
+    type Err = ParseError;
 
     fn from_str(token: &str) -> Result<Self, Self::Err> {
-        let (from, to) = token.split_once(" -> ").expect("Eeps");
+        let (from, to) = token
+            .split_once(" -> ")
+            .ok_or_else(|| ParseError::new(1, "expected 'XY -> Z'"))?;
+
+        if from.len() != 2 || !from.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(ParseError::at(
+                token,
+                from,
+                format!("invalid pair '{}'", from),
+            ));
+        }
+        if to.len() != 1 || !to.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(ParseError::at(
+                token,
+                to,
+                format!("invalid element '{}'", to),
+            ));
+        }
+
         Ok(Rule {
             from: from.to_string(),
             to: to.to_string(),
@@ -20,12 +38,32 @@ impl FromStr for Rule {
     }
 }
 
-fn parse_rules(rule_tokens: &[&str]) -> Vec<Rule> {
-    rule_tokens
-        .iter()
-        .skip(1)
-        .flat_map(|&token| str::parse::<Rule>(token))
-        .collect::<Vec<_>>()
+/// Parse the rules, `rule_tokens` starts with the empty line that follows the template. Every pair can
+/// have only one rule.
+fn parse_rules(rule_tokens: &[&str]) -> Result<Vec<Rule>, ParseError> {
+    if rule_tokens.first().is_some_and(|token| !token.is_empty()) {
+        return Err(ParseError::new(1, "expected an empty line after the template").on_line(2));
+    }
+
+    let mut lines = HashMap::new();
+    let mut rules = vec![];
+
+    for (n, &token) in rule_tokens.iter().enumerate().skip(1) {
+        let rule = str::parse::<Rule>(token).map_err(|e| e.on_line(n + 2))?;
+
+        if let Some(first) = lines.insert(rule.from.clone(), n + 2) {
+            return Err(ParseError::new(
+                1,
+                format!(
+                    "duplicate rule for '{}', first on line {}",
+                    rule.from, first
+                ),
+            )
+            .on_line(n + 2));
+        }
+        rules.push(rule);
+    }
+    Ok(rules)
 }
 
 fn extract_pairs(template: &str) -> HashMap<String, i64> {
@@ -39,7 +77,7 @@ fn extract_pairs(template: &str) -> HashMap<String, i64> {
     result
 }
 
-fn apply_rules(rules: &Vec<Rule>, pairs: &HashMap<String, i64>) -> HashMap<String, i64> {
+fn apply_rules(rules: &[Rule], pairs: &HashMap<String, i64>) -> HashMap<String, i64> {
     let mut result: HashMap<String, i64> = HashMap::new();
 
     for (pair, count) in pairs {
@@ -59,7 +97,7 @@ fn count_letters(pairs: &HashMap<String, i64>, last: char) -> HashMap<char, i64>
     let mut result = HashMap::new();
 
     for p in pairs {
-        *result.entry(p.0.chars().nth(0).unwrap()).or_insert(0) += p.1
+        *result.entry(p.0.chars().next().unwrap()).or_insert(0) += p.1
     }
 
     // Because we didnt count the last letter of the last pair of the original template, we need to add one there
@@ -69,64 +107,71 @@ fn count_letters(pairs: &HashMap<String, i64>, last: char) -> HashMap<char, i64>
     result
 }
 
-fn part1() {
-    let input = common::import("days/day14/input.txt");
-    let b = input.iter().map(|s| s.as_str()).collect::<Vec<_>>();
-
-    let (template, rule_tokens) = b.split_at(1);
-    let rules = parse_rules(rule_tokens);
+struct ExtendedPolymerization {
+    template: String,
+    rules: Vec<Rule>,
+}
 
-    let template = template.first().unwrap().to_string();
-    let last = template.chars().last().unwrap();
-    let mut pairs = extract_pairs(&template);
+impl ExtendedPolymerization {
+    fn difference_after(&self, steps: usize) -> i64 {
+        let last = self.template.chars().last().unwrap();
+        let mut pairs = extract_pairs(&self.template);
 
-    for _ in 1..=10 {
-        pairs = apply_rules(&rules, &pairs);
-    }
+        for _ in 1..=steps {
+            pairs = apply_rules(&self.rules, &pairs);
+        }
 
-    let counts = count_letters(&pairs, last);
+        let counts = count_letters(&pairs, last);
 
-    let (min, max) = (
-        counts.iter().min_by_key(|e| e.1).unwrap().1,
-        counts.iter().max_by_key(|e| e.1).unwrap().1,
-    );
-    assert_eq!(max - min, 2345);
-    println!("Day 14 part 1 : {}", max - min);
+        let (min, max) = (
+            counts.iter().min_by_key(|e| e.1).unwrap().1,
+            counts.iter().max_by_key(|e| e.1).unwrap().1,
+        );
+        max - min
+    }
 }
 
-fn part2() {
-    let input = common::import("days/day14/input.txt");
-    let b = input.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+impl Solution for ExtendedPolymerization {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let b = input.lines().collect::<Vec<_>>();
 
-    let (template, rule_tokens) = b.split_at(1);
-    let rules = parse_rules(rule_tokens);
-    let template = template.first().unwrap().to_string();
-    let last = template.chars().last().unwrap();
-    let mut pairs = extract_pairs(&template);
+        let template = b
+            .first()
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| ParseError::new(1, "missing polymer template").on_line(1))?;
 
-    for _ in 1..=40 {
-        pairs = apply_rules(&rules, &pairs);
+        Ok(ExtendedPolymerization {
+            template: template.to_string(),
+            rules: parse_rules(&b[1..])?,
+        })
     }
 
-    let counts = count_letters(&pairs, last);
+    fn part1(&self) -> Answer {
+        let difference = self.difference_after(10);
 
-    let (min, max) = (
-        counts.iter().min_by_key(|e| e.1).unwrap().1,
-        counts.iter().max_by_key(|e| e.1).unwrap().1,
-    );
+        difference.into()
+    }
+
+    fn part2(&self) -> Answer {
+        let difference = self.difference_after(40);
 
-    assert_eq!(max - min, 2432786807053);
-    println!("Day 14 part 2 : {}", max - min);
+        difference.into()
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<ExtendedPolymerization>(
+        14,
+        "Extended Polymerization",
+        env!("CARGO_MANIFEST_DIR"),
+    );
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{apply_rules, count_letters, extract_pairs, parse_rules};
+    use common::Solution;
+
+    use crate::{apply_rules, count_letters, extract_pairs, parse_rules, ExtendedPolymerization};
 
     static TEST_INPUT1: &[&str] = &[
         "NNCB", "", "CH -> B", "HH -> N", "CB -> H", "NH -> C", "HB -> C", "HC -> B", "HN -> C",
@@ -138,7 +183,7 @@ mod tests {
     fn part1_test1() {
         let (template, rule_tokens) = TEST_INPUT1.split_at(1);
 
-        let rules = parse_rules(rule_tokens);
+        let rules = parse_rules(rule_tokens).unwrap();
 
         let template = template.first().unwrap().to_string();
 
@@ -191,7 +236,7 @@ mod tests {
     fn part2_test1() {
         let (template, rule_tokens) = TEST_INPUT1.split_at(1);
 
-        let rules = parse_rules(rule_tokens);
+        let rules = parse_rules(rule_tokens).unwrap();
 
         let template = template.first().unwrap().to_string();
 
@@ -223,4 +268,23 @@ mod tests {
 
         assert_eq!(max - min, 2188189693529);
     }
+
+    #[test]
+    fn parse_test1() {
+        let error = |input: &str| {
+            ExtendedPolymerization::parse(input)
+                .err()
+                .map(|e| e.to_string())
+        };
+
+        assert_eq!(
+            error("NNCB\nCH -> B\nHH -> N"),
+            Some("2:1: expected an empty line after the template".into())
+        );
+        assert_eq!(
+            error("NNCB\n\nCH -> B\nHH -> N\nCH -> C"),
+            Some("5:1: duplicate rule for 'CH', first on line 3".into())
+        );
+        assert_eq!(error("NNCB\n\nCH -> B\nHH -> N"), None);
+    }
 }
@@ -1,131 +1,194 @@
-use std::collections::HashMap;
-
-use common::{extends, Coordinate};
-use pathfinding::prelude::astar;
-
-fn successors(
-    p: &Coordinate<i64>,
-    map: &HashMap<Coordinate<i64>, u32>,
-    limits: (i64, i64),
-    topleft: &Coordinate<i64>,
-    bottomright: &Coordinate<i64>,
-) -> Vec<(Coordinate<i64>, u32)> {
-    let mut successors = vec![];
-
-    let (base_width, base_height) = (bottomright.x + 1, bottomright.y + 1);
-
-    let (y_mul, y_offs) = (p.y / base_height, p.y % base_height);
-    for x in [-1, 1] {
-        let x = p.x + x;
-
-        let (x_mul, x_offs) = (x / base_width, x % base_width);
-
-        if x >= topleft.x && x_mul < limits.0 && y_mul < limits.1 {
-            if let Some((_, n)) = map.get_key_value(&Coordinate::new(x_offs, y_offs, 0)) {
-                // We need to check in the map using mod math, but add the neigbors in normal scale/coordinates
-                successors.push((
-                    Coordinate::new(x, p.y, p.z),
-                    calculate_cost(x_mul, y_mul, n),
-                ));
-            }
+mod render;
+
+use common::{Answer, Bounds, Grid, Neighborhood, ParseError, Point2, Registry, Solution};
+pub use render::{ansi, ppm};
+
+/// The risk levels of the cave, the puzzle input being one tile of a larger map. Every tile to the right
+/// or down adds one to the risk of the tile before it, where a risk above 9 wraps around to 1. The tiled
+/// map is never built, risks are worked out when asked for.
+#[derive(Clone)]
+pub struct RiskMap {
+    tile: Grid<u32>,
+    across: usize,
+    down: usize,
+}
+
+impl RiskMap {
+    /// The single `tile`, use `tiled` for more.
+    pub fn new(tile: Grid<u32>) -> RiskMap {
+        RiskMap {
+            tile,
+            across: 1,
+            down: 1,
         }
     }
 
-    let (x_mul, x_offs) = (p.x / base_width, p.x % base_width);
-    for y in [-1, 1] {
-        let y = p.y + y;
+    /// The tile in `input`, one digit from 1 to 9 per position. There has to be at least one.
+    pub fn parse(input: &[&str]) -> Result<RiskMap, ParseError> {
+        let tile = Grid::parse(input, |c| {
+            c.to_digit(10)
+                .filter(|&risk| risk > 0)
+                .ok_or(format!("Invalid risk level '{}'", c))
+        })?;
+        if tile.width() * tile.height() == 0 {
+            return Err(ParseError::new(1, "No risk levels"));
+        }
+
+        Ok(RiskMap::new(tile))
+    }
 
-        let (y_mul, y_offs) = (y / base_height, y % base_height);
+    /// The same tile repeated `across` times to the right and `down` times downwards.
+    pub fn tiled(&self, across: usize, down: usize) -> RiskMap {
+        RiskMap {
+            tile: self.tile.clone(),
+            across,
+            down,
+        }
+    }
 
-        if y >= topleft.y && x_mul < limits.0 && y_mul < limits.1 {
-            if let Some((_, n)) = map.get_key_value(&Coordinate::new(x_offs, y_offs, 0)) {
-                successors.push((
-                    Coordinate::new(p.x, y, p.z),
-                    calculate_cost(x_mul, y_mul, n),
-                ));
+    pub fn width(&self) -> usize {
+        self.tile.width() * self.across
+    }
+
+    pub fn height(&self) -> usize {
+        self.tile.height() * self.down
+    }
+
+    pub fn bounds(&self) -> Bounds<Point2<i64>> {
+        if self.width() == 0 || self.height() == 0 {
+            Bounds::empty()
+        } else {
+            Bounds::new(
+                Point2::new(0, 0),
+                Point2::new(self.width() as i64 - 1, self.height() as i64 - 1),
+            )
+        }
+    }
+
+    pub fn risk(&self, position: &Point2<i64>) -> Option<u32> {
+        if !self.bounds().contains(position) {
+            return None;
+        }
+        let (width, height) = (self.tile.width() as i64, self.tile.height() as i64);
+        let tile = (position.x / width + position.y / height) as u32;
+        let risk = self.tile[&Point2::new(position.x % width, position.y % height)];
+
+        Some((risk + tile - 1) % 9 + 1)
+    }
+
+    fn offset(&self, position: &Point2<i64>) -> usize {
+        position.y as usize * self.width() + position.x as usize
+    }
+
+    /// The lowest total risk of going from `from` to `to`, entering a position costs its risk.
+    pub fn lowest_risk(&self, from: &Point2<i64>, to: &Point2<i64>) -> Option<u32> {
+        self.route(from, to).map(|route| route.risk)
+    }
+
+    /// The route with the lowest total risk from `from` to `to`. Dijkstra over the whole map, as a step
+    /// never costs more than 9 the queue is a ring of 10 buckets of positions, one for every cost from
+    /// the current one on.
+    pub fn route(&self, from: &Point2<i64>, to: &Point2<i64>) -> Option<Route> {
+        let bounds = self.bounds();
+        if !bounds.contains(from) || !bounds.contains(to) {
+            return None;
+        }
+
+        let mut risks = vec![u32::MAX; self.width() * self.height()];
+        // The position every position was reached from on its cheapest known route
+        let mut previous = vec![None; self.width() * self.height()];
+        let mut buckets = vec![vec![]; 10];
+        let mut queued = 1;
+
+        risks[self.offset(from)] = 0;
+        buckets[0].push(*from);
+
+        let mut risk = 0;
+        while queued > 0 {
+            let Some(position) = buckets[risk as usize % 10].pop() else {
+                risk += 1;
+                continue;
+            };
+            queued -= 1;
+
+            // Positions are queued again when a cheaper way shows up, skip the outdated ones
+            if risks[self.offset(&position)] < risk {
+                continue;
+            }
+            if position == *to {
+                let mut positions = vec![position];
+                while let Some(p) = previous[self.offset(&positions[positions.len() - 1])] {
+                    positions.push(p);
+                }
+                positions.reverse();
+
+                return Some(Route { positions, risk });
+            }
+
+            for neighbor in Neighborhood::VON_NEUMANN.inside(position, bounds) {
+                let total = risk + self.risk(&neighbor).unwrap();
+                let n = self.offset(&neighbor);
+
+                if total < risks[n] {
+                    risks[n] = total;
+                    previous[n] = Some(position);
+                    buckets[total as usize % 10].push(neighbor);
+                    queued += 1;
+                }
             }
         }
+        None
     }
+}
 
-    successors
+/// A way through the cave, start and end included, with the total risk of entering all but the start.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Route {
+    pub positions: Vec<Point2<i64>>,
+    pub risk: u32,
 }
 
-fn calculate_cost(y_mul: i64, x_mul: i64, n: &u32) -> u32 {
-    let mut n: u32 = n + x_mul as u32 + y_mul as u32;
-    if n > 9 {
-        n = 1 + n % 10;
-    }
-    n
+struct Chiton {
+    map: RiskMap,
 }
 
-fn part1() {
-    let input = common::import("days/day15/input.txt");
-    let b = input.iter().map(|s| s.as_str()).collect::<Vec<_>>();
-    let map = common::enumerate_xy(&b, &|_, _, c| c.to_digit(10).unwrap());
-    let (top_left, bottom_right) = extends(map.keys().copied());
+impl Solution for Chiton {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Chiton {
+            map: RiskMap::parse(&input.lines().collect::<Vec<_>>())?,
+        })
+    }
 
-    let path = astar(
-        &top_left,
-        |p: &Coordinate<i64>| successors(p, &map, (1, 1), &top_left, &bottom_right),
-        |p: &Coordinate<i64>| p.crow(&bottom_right) as u32,
-        |p: &Coordinate<i64>| *p == bottom_right,
-    );
+    fn part1(&self) -> Answer {
+        lowest_total_risk(&self.map).map_or("no path".into(), Answer::from)
+    }
 
-    let cost = if let Some(p) = path {
-        p.1
-    } else {
-        panic!("No path found");
-    };
+    fn part2(&self) -> Answer {
+        lowest_total_risk(&self.map.tiled(5, 5)).map_or("no path".into(), Answer::from)
+    }
+}
 
-    assert_eq!(cost, 390);
+/// The route with the lowest total risk from the top left to the bottom right of `map`.
+pub fn safest_route(map: &RiskMap) -> Option<Route> {
+    let bounds = map.bounds();
 
-    println!("Day 15 part 1 : Cost of path is {}", cost);
+    map.route(&bounds.min()?, &bounds.max()?)
 }
 
-fn part2() {
-    let input = common::import("days/day15/input.txt");
-    let b = input.iter().map(|s| s.as_str()).collect::<Vec<_>>();
-    let map = common::enumerate_xy(&b, &|_, _, c| c.to_digit(10).unwrap());
-
-    let (top_left, bottom_right) = extends(map.keys().copied());
-    let goal = Coordinate::new(
-        5 * (bottom_right.x + 1) - 1,
-        5 * (bottom_right.y + 1) - 1,
-        bottom_right.z,
-    );
-
-    let heuristic = |p: &Coordinate<i64>| p.crow(&goal) as u32;
-    let is_at_goal = |p: &Coordinate<i64>| *p == Coordinate::new(goal.x, goal.y, 0);
-
-    let path = astar(
-        &top_left,
-        |p: &Coordinate<i64>| successors(p, &map, (5, 5), &top_left, &bottom_right),
-        heuristic,
-        is_at_goal,
-    );
-
-    let cost = if let Some(p) = path {
-        p.1
-    } else {
-        panic!("No path found");
-    };
-
-    assert_eq!(cost, 2814);
-
-    println!("Day 15 part 1 : Cost of path is {}", cost);
+/// The total risk of the safest route, `None` when there is no route at all.
+fn lowest_total_risk(map: &RiskMap) -> Option<u32> {
+    safest_route(map).map(|route| route.risk)
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<Chiton>(15, "Chiton", env!("CARGO_MANIFEST_DIR"));
 }
 
 #[cfg(test)]
 mod tests {
-    use common::{extends, Coordinate};
-    use pathfinding::prelude::astar;
+    use common::Point2;
 
-    use crate::successors;
+    use crate::{lowest_total_risk, safest_route, RiskMap};
 
     static TEST_INPUT1: &[&str] = &[
         "1163751742",
@@ -142,27 +205,25 @@ mod tests {
 
     #[test]
     fn part1_test1() {
-        let map = common::enumerate_xy(TEST_INPUT1, &|_, _, c| c.to_digit(10).unwrap());
-
-        let (top_left, bottom_right) = extends(map.keys().copied());
-
-        let heuristic = |p: &Coordinate<i64>| p.crow(&bottom_right) as u32;
-        let is_at_goal = |p: &Coordinate<i64>| *p == bottom_right;
-
-        let path = astar(
-            &top_left,
-            |p: &Coordinate<i64>| successors(p, &map, (1, 1), &top_left, &bottom_right),
-            heuristic,
-            is_at_goal,
+        let map = RiskMap::parse(TEST_INPUT1).unwrap();
+
+        assert_eq!(lowest_total_risk(&map), Some(40));
+
+        let route = safest_route(&map).unwrap();
+        assert_eq!(route.positions.len(), 19);
+        assert_eq!(route.positions[0], Point2::new(0, 0));
+        assert_eq!(route.positions[18], Point2::new(9, 9));
+        assert_eq!(
+            route.positions[1..]
+                .iter()
+                .map(|p| map.risk(p).unwrap())
+                .sum::<u32>(),
+            40
         );
-
-        let cost = if let Some(p) = path {
-            p.1
-        } else {
-            panic!("No path found");
-        };
-
-        assert_eq!(cost, 40);
+        assert!(route
+            .positions
+            .windows(2)
+            .all(|w| w[0].manhattan(&w[1]) == 1));
     }
 
     static TEST_INPUT2: &[&str] = &[
@@ -220,57 +281,47 @@ mod tests {
 
     #[test]
     fn part2_test1() {
-        let map = common::enumerate_xy(TEST_INPUT2, &|_, _, c| c.to_digit(10).unwrap());
-
-        let (top_left, bottom_right) = extends(map.keys().copied());
+        let map = RiskMap::parse(TEST_INPUT2).unwrap();
 
-        let heuristic = |p: &Coordinate<i64>| p.crow(&bottom_right) as u32;
-        let is_at_goal =
-            |p: &Coordinate<i64>| *p == Coordinate::new(bottom_right.x, bottom_right.y, 0);
-
-        let path = astar(
-            &top_left,
-            |p: &Coordinate<i64>| successors(p, &map, (1, 1), &top_left, &bottom_right),
-            heuristic,
-            is_at_goal,
-        );
-
-        let cost = if let Some(p) = path {
-            p.1
-        } else {
-            panic!("No path found");
-        };
-
-        assert_eq!(cost, 315);
+        assert_eq!(lowest_total_risk(&map), Some(315));
     }
 
     #[test]
     fn part2_test2() {
-        let map = common::enumerate_xy(TEST_INPUT1, &|_, _, c| c.to_digit(10).unwrap());
+        let map = RiskMap::parse(TEST_INPUT1).unwrap().tiled(5, 5);
+        let full = RiskMap::parse(TEST_INPUT2).unwrap();
 
-        let (top_left, bottom_right) = extends(map.keys().copied());
-        let goal = Coordinate::new(
-            5 * (bottom_right.x + 1) - 1,
-            5 * (bottom_right.y + 1) - 1,
-            bottom_right.z,
-        );
+        assert_eq!((map.width(), map.height()), (50, 50));
+        assert!(map.bounds().cells().all(|p| map.risk(&p) == full.risk(&p)));
 
-        let heuristic = |p: &Coordinate<i64>| p.crow(&goal) as u32;
-        let is_at_goal = |p: &Coordinate<i64>| *p == Coordinate::new(goal.x, goal.y, 0);
+        assert_eq!(lowest_total_risk(&map), Some(315));
+    }
 
-        let path = astar(
-            &top_left,
-            |p: &Coordinate<i64>| successors(p, &map, (5, 5), &top_left, &bottom_right),
-            heuristic,
-            is_at_goal,
+    #[test]
+    fn tiled_test1() {
+        let map = RiskMap::parse(&["8"]).unwrap().tiled(12, 2);
+
+        assert_eq!(
+            (0..12)
+                .map(|x| map.risk(&Point2::new(x, 0)).unwrap())
+                .collect::<Vec<_>>(),
+            vec![8, 9, 1, 2, 3, 4, 5, 6, 7, 8, 9, 1]
+        );
+        assert_eq!(map.risk(&Point2::new(11, 1)), Some(2));
+        assert_eq!(map.risk(&Point2::new(12, 0)), None);
+        assert_eq!(
+            map.lowest_risk(&Point2::new(0, 0), &Point2::new(3, 1)),
+            Some(9 + 1 + 2 + 3)
+        );
+        assert!(RiskMap::parse(&["120"]).is_err());
+        assert_eq!(
+            RiskMap::parse(&[]).err().map(|e| e.to_string()),
+            Some("No risk levels".into())
+        );
+        assert!(RiskMap::parse(&[""]).is_err());
+        assert_eq!(
+            lowest_total_risk(&RiskMap::parse(&["8"]).unwrap().tiled(0, 1)),
+            None
         );
-
-        let cost = if let Some(p) = path {
-            p.1
-        } else {
-            panic!("No path found");
-        };
-
-        assert_eq!(cost, 315);
     }
 }
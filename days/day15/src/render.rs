@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+
+use ansi_control_codes::control_sequences::{GraphicRendition, SGR};
+use common::Point2;
+
+use crate::{RiskMap, Route};
+
+/// The risk levels as digits for a terminal, one line per row, the route in bold red and the rest faint.
+pub fn ansi(map: &RiskMap, route: &Route) -> String {
+    let on_route = route.positions.iter().collect::<HashSet<_>>();
+    let mut result = String::new();
+
+    for y in 0..map.height() as i64 {
+        let mut highlighted = None;
+
+        for x in 0..map.width() as i64 {
+            let position = Point2::new(x, y);
+
+            // Only switch the rendition where it changes, the tiled map is big enough as it is
+            let highlight = on_route.contains(&position);
+            if highlighted != Some(highlight) {
+                let rendition = if highlight {
+                    vec![
+                        GraphicRendition::HighIntensity,
+                        GraphicRendition::RedForeground,
+                    ]
+                } else {
+                    vec![GraphicRendition::LowIntensity]
+                };
+                result += &format!("{}{}", SGR(None), SGR(Some(rendition)));
+                highlighted = Some(highlight);
+            }
+            result += &map.risk(&position).unwrap().to_string();
+        }
+        result += &format!("{}\n", SGR(None));
+    }
+    result
+}
+
+/// The map as a binary PPM image with `scale` pixels for every position. Risk levels are shades of grey
+/// from light for 1 to dark for 9, the route is red.
+pub fn ppm(map: &RiskMap, route: &Route, scale: usize) -> Vec<u8> {
+    let on_route = route.positions.iter().collect::<HashSet<_>>();
+    let (width, height) = (map.width() * scale, map.height() * scale);
+
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    image.reserve(width * height * 3);
+
+    for y in 0..height {
+        for x in 0..width {
+            let position = Point2::new((x / scale) as i64, (y / scale) as i64);
+
+            if on_route.contains(&position) {
+                image.extend([255, 0, 0]);
+            } else {
+                let shade = 255 - (map.risk(&position).unwrap() * 25) as u8;
+                image.extend([shade; 3]);
+            }
+        }
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ansi, ppm};
+    use crate::{safest_route, RiskMap};
+
+    #[test]
+    fn render_test1() {
+        let map = RiskMap::parse(&["19", "11"]).unwrap();
+        let route = safest_route(&map).unwrap();
+
+        assert_eq!(
+            ansi(&map, &route),
+            "\x1b[0m\x1b[1;31m1\x1b[0m\x1b[2m9\x1b[0m\n\x1b[0m\x1b[1;31m11\x1b[0m\n"
+        );
+
+        let image = ppm(&map, &route, 2);
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image.len(), header.len() + 4 * 4 * 3);
+        // The top right 9 is dark grey and off the route
+        assert_eq!(&image[header.len() + 2 * 3..][..3], &[30, 30, 30]);
+        assert_eq!(&image[header.len()..][..3], &[255, 0, 0]);
+    }
+}
@@ -0,0 +1,179 @@
+use common::ParseError;
+
+/// Reads bits straight from a hexadecimal transmission, most significant bit of each digit first.
+pub struct BitReader<'a> {
+    hex: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(hex: &'a str) -> BitReader<'a> {
+        BitReader {
+            hex: hex.as_bytes(),
+            position: 0,
+        }
+    }
+
+    /// Number of bits read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.hex.len() * 4 - self.position
+    }
+
+    /// An error about the bit at `position`, reported at the column of the hex digit holding it.
+    pub fn error_at(&self, position: usize, message: impl Into<String>) -> ParseError {
+        ParseError::new(position / 4 + 1, message)
+    }
+
+    /// Read the next `width` bits, at most 64, as a number.
+    pub fn read(&mut self, width: usize) -> Result<u64, ParseError> {
+        assert!(width <= 64, "can not read {} bits into a u64", width);
+
+        Ok(self.read_u128(width)? as u64)
+    }
+
+    /// Read the next `width` bits, at most 128, as a number. On an error nothing is read.
+    pub fn read_u128(&mut self, width: usize) -> Result<u128, ParseError> {
+        assert!(width <= 128, "can not read {} bits into a u128", width);
+
+        if width > self.remaining() {
+            return Err(self.error_at(
+                self.position,
+                format!(
+                    "transmission truncated, wanted {} bits at bit {} but only {} left",
+                    width,
+                    self.position,
+                    self.remaining()
+                ),
+            ));
+        }
+
+        let mut value = 0u128;
+        let mut position = self.position;
+        let mut left = width;
+
+        // Take as many bits as possible from every digit instead of going bit by bit
+        while left > 0 {
+            let index = position / 4;
+            let digit = (self.hex[index] as char)
+                .to_digit(16)
+                .ok_or_else(|| self.error_at(position, "expected a hexadecimal digit"))?;
+
+            let offset = position % 4;
+            let take = left.min(4 - offset);
+            let bits = (digit >> (4 - offset - take)) & ((1 << take) - 1);
+
+            value = (value << take) | bits as u128;
+            position += take;
+            left -= take;
+        }
+
+        self.position = position;
+        Ok(value)
+    }
+}
+
+/// Writes bits into a hexadecimal transmission, the counterpart of `BitReader`.
+#[derive(Default)]
+pub struct BitWriter {
+    hex: String,
+    digit: u32,
+    position: usize,
+}
+
+impl BitWriter {
+    /// Number of bits written so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Write the lowest `width` bits of `value`, which must fit in them.
+    pub fn write(&mut self, value: u64, width: usize) {
+        assert!(
+            width >= 64 || value >> width == 0,
+            "{} does not fit in {} bits",
+            value,
+            width
+        );
+
+        for bit in (0..width).rev() {
+            self.digit = (self.digit << 1) | ((value >> bit) & 1) as u32;
+            self.position += 1;
+
+            if self.position.is_multiple_of(4) {
+                self.hex.push(
+                    char::from_digit(self.digit, 16)
+                        .unwrap()
+                        .to_ascii_uppercase(),
+                );
+                self.digit = 0;
+            }
+        }
+    }
+
+    /// The transmission, padded with zero bits to a whole hex digit.
+    pub fn finish(mut self) -> String {
+        while !self.position.is_multiple_of(4) {
+            self.write(0, 1);
+        }
+        self.hex
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BitReader, BitWriter};
+
+    #[test]
+    fn reader_test1() {
+        let mut reader = BitReader::new("D2FE28");
+
+        assert_eq!(reader.remaining(), 24);
+        assert_eq!(reader.read(3), Ok(0b110));
+        assert_eq!(reader.read(3), Ok(0b100));
+        assert_eq!(reader.read(5), Ok(0b10111));
+        assert_eq!(reader.position(), 11);
+        assert_eq!(reader.read(0), Ok(0));
+        assert_eq!(reader.read(13), Ok(0b1111000101000));
+        assert_eq!(reader.remaining(), 0);
+
+        let error = reader.read(1).unwrap_err();
+        assert_eq!(error.column, 7);
+
+        let mut reader = BitReader::new("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF0");
+        assert_eq!(reader.read_u128(128), Ok(u128::MAX));
+        assert_eq!(reader.read(4), Ok(0));
+
+        let mut reader = BitReader::new("1G");
+        assert_eq!(reader.read(2), Ok(0));
+        assert_eq!(reader.read(4).unwrap_err().column, 2);
+
+        // A bad digit part way through a read leaves the reader where it was
+        let mut reader = BitReader::new("F0G0");
+        assert_eq!(reader.read(4), Ok(0xF));
+        assert_eq!(reader.read(12).unwrap_err().column, 3);
+        assert_eq!(reader.position(), 4);
+        assert_eq!(reader.read(4), Ok(0));
+    }
+
+    #[test]
+    fn writer_test1() {
+        let mut writer = BitWriter::default();
+
+        writer.write(0b110, 3);
+        writer.write(0b100, 3);
+        writer.write(0b10111, 5);
+        writer.write(0b11110, 5);
+        writer.write(0b00101, 5);
+        assert_eq!(writer.position(), 21);
+        assert_eq!(writer.finish(), "D2FE28");
+
+        let mut writer = BitWriter::default();
+        writer.write(u64::MAX, 64);
+        writer.write(1, 1);
+        assert_eq!(writer.finish(), "FFFFFFFFFFFFFFFF8");
+    }
+}
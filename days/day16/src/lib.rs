@@ -1,159 +1,620 @@
-use std::{str, vec};
+mod bits;
 
-#[derive(PartialEq, Debug)]
-enum LenghtTypeId {
-    TotalLengthInBits(i32),
-    TotalSubPackets(i32),
+use std::fmt;
+
+use bits::{BitReader, BitWriter};
+use common::{Answer, ParseError, Registry, Solution};
+
+/// How an operator packet records the extent of its sub-packets, the length type ID bit. The length or
+/// count itself always follows from the sub-packets.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum LengthType {
+    /// The total length of the sub-packets in bits.
+    Bits,
+    /// The number of sub-packets.
+    SubPackets,
 }
 
-#[derive(PartialEq, Debug)]
-enum SubPacket {
-    LITERAL(i32),
-    OPERATOR(LenghtTypeId, Vec<SubPacket>),
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Operator {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
 }
 
-#[derive(PartialEq, Debug)]
-struct Packet {
-    version: i32,
-    subpackets: Vec<SubPacket>,
+impl Operator {
+    fn from_type_id(type_id: u64) -> Option<Operator> {
+        match type_id {
+            0 => Some(Operator::Sum),
+            1 => Some(Operator::Product),
+            2 => Some(Operator::Minimum),
+            3 => Some(Operator::Maximum),
+            5 => Some(Operator::GreaterThan),
+            6 => Some(Operator::LessThan),
+            7 => Some(Operator::EqualTo),
+            _ => None,
+        }
+    }
+
+    fn type_id(&self) -> u64 {
+        match self {
+            Operator::Sum => 0,
+            Operator::Product => 1,
+            Operator::Minimum => 2,
+            Operator::Maximum => 3,
+            Operator::GreaterThan => 5,
+            Operator::LessThan => 6,
+            Operator::EqualTo => 7,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Operator::Sum => "sum",
+            Operator::Product => "product",
+            Operator::Minimum => "min",
+            Operator::Maximum => "max",
+            Operator::GreaterThan => "gt",
+            Operator::LessThan => "lt",
+            Operator::EqualTo => "eq",
+        }
+    }
+
+    fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Operator::GreaterThan | Operator::LessThan | Operator::EqualTo
+        )
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum Payload {
+    Literal(u64),
+    Operator(Operator, LengthType, Vec<Packet>),
 }
 
-fn decode_hex_to_binary(input: &str) -> String {
-    input
-        .chars()
-        .map(|c| format!("{:04b}", c.to_digit(16).expect("Invalid input token")))
-        //.inspect(|f| println!("{}", f))
-        .collect()
+#[derive(PartialEq, Clone, Debug)]
+pub struct Packet {
+    pub version: u8,
+    pub payload: Payload,
 }
 
-fn decode_bits_to_packet(input: &str) -> Option<Packet> {
-    let version = i32::from_str_radix(&input[0..3], 2).ok()?;
+/// Largest number of sub-packets and bits the two length type headers can hold.
+const MAX_SUB_PACKETS: usize = (1 << 11) - 1;
+const MAX_LENGTH_IN_BITS: usize = (1 << 15) - 1;
+
+impl Packet {
+    pub fn literal(version: u8, value: u64) -> Packet {
+        Packet {
+            version,
+            payload: Payload::Literal(value),
+        }
+    }
+
+    /// An operator packet, without a `length_type` it counts the sub-packets when there are few enough,
+    /// the shorter header.
+    pub fn operator(
+        version: u8,
+        operator: Operator,
+        length_type: Option<LengthType>,
+        packets: Vec<Packet>,
+    ) -> Packet {
+        let length_type = length_type.unwrap_or(if packets.len() <= MAX_SUB_PACKETS {
+            LengthType::SubPackets
+        } else {
+            LengthType::Bits
+        });
+
+        Packet {
+            version,
+            payload: Payload::Operator(operator, length_type, packets),
+        }
+    }
+
+    /// Number of bits the packet takes when encoded, without padding.
+    pub fn encoded_len(&self) -> usize {
+        6 + match &self.payload {
+            Payload::Literal(value) => 5 * literal_groups(*value),
+            Payload::Operator(_, length_type, packets) => {
+                let header = match length_type {
+                    LengthType::Bits => 16,
+                    LengthType::SubPackets => 12,
+                };
+                header + packets.iter().map(Packet::encoded_len).sum::<usize>()
+            }
+        }
+    }
+
+    pub fn version_sum(&self) -> u64 {
+        self.version as u64
+            + match &self.payload {
+                Payload::Literal(_) => 0,
+                Payload::Operator(_, _, packets) => packets.iter().map(Packet::version_sum).sum(),
+            }
+    }
+
+    /// The value of the expression, `None` when a sum or product does not fit in 64 bits.
+    pub fn evaluate(&self) -> Option<u64> {
+        let (operator, packets) = match &self.payload {
+            Payload::Literal(value) => return Some(*value),
+            Payload::Operator(operator, _, packets) => (operator, packets),
+        };
+
+        let values = packets
+            .iter()
+            .map(Packet::evaluate)
+            .collect::<Option<Vec<_>>>()?;
+        let (first, second) = (values.first(), values.get(1));
+
+        match operator {
+            Operator::Sum => values.iter().try_fold(0u64, |a, &v| a.checked_add(v)),
+            Operator::Product => values.iter().try_fold(1u64, |a, &v| a.checked_mul(v)),
+            Operator::Minimum => Some(values.iter().copied().min().unwrap_or_default()),
+            Operator::Maximum => Some(values.iter().copied().max().unwrap_or_default()),
+            Operator::GreaterThan => Some((first > second) as u64),
+            Operator::LessThan => Some((first < second) as u64),
+            Operator::EqualTo => Some((first == second) as u64),
+        }
+    }
 
-    Some(Packet {
-        version,
-        subpackets: decode_bits_to_sub_packets(&input[3..]),
-    })
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        match &self.payload {
+            Payload::Literal(value) => write!(f, "(literal v{} {})", self.version, value),
+            Payload::Operator(operator, _, packets) => {
+                write!(f, "({} v{}", operator.name(), self.version)?;
+                for packet in packets {
+                    write!(f, "\n{:indent$}", "", indent = 2 * (depth + 1))?;
+                    packet.fmt_indented(f, depth + 1)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
 }
 
-fn decode_bits_to_sub_packets(input: &str) -> Vec<SubPacket> {
-    let mut result = vec![];
+/// Prints the packet as an S-expression like `(sum 1 (max 2 3))`. The alternate form `{:#}` puts every
+/// sub-packet on its own indented line and includes the versions.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return self.fmt_indented(f, 0);
+        }
 
-    let mut offset = 0usize;
-    while let Some((sub_packet, len)) =
-        match i32::from_str_radix(&input[offset..offset + 3], 2).unwrap() {
-            4 => decode_bits_to_literal(&input[offset + 3..]),
-            _ => decode_bits_to_operator(&input[offset + 3..]),
+        match &self.payload {
+            Payload::Literal(value) => write!(f, "{}", value),
+            Payload::Operator(operator, _, packets) => {
+                write!(f, "({}", operator.name())?;
+                for packet in packets {
+                    write!(f, " {}", packet)?;
+                }
+                write!(f, ")")
+            }
         }
-    {
-        offset += 3 + len;
+    }
+}
 
-        result.push(sub_packet);
+/// Number of 4 bit groups a literal takes, zero still needs one.
+fn literal_groups(value: u64) -> usize {
+    ((64 - value.leading_zeros() as usize).div_ceil(4)).max(1)
+}
+
+/// A packet that can not be represented in the BITS format.
+#[derive(PartialEq, Debug)]
+pub enum EncodeError {
+    Version(u8),
+    TooManySubPackets(usize),
+    SubPacketsTooLong(usize),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::Version(v) => write!(f, "version {} does not fit in 3 bits", v),
+            EncodeError::TooManySubPackets(n) => write!(
+                f,
+                "{} sub-packets, at most {} can be counted",
+                n, MAX_SUB_PACKETS
+            ),
+            EncodeError::SubPacketsTooLong(n) => write!(
+                f,
+                "sub-packets take {} bits, at most {} can be recorded",
+                n, MAX_LENGTH_IN_BITS
+            ),
+        }
     }
+}
+
+impl std::error::Error for EncodeError {}
 
-    result
+/// Decode the outermost packet of a hexadecimal transmission, padding after it is ignored.
+pub fn decode(transmission: &str) -> Result<Packet, ParseError> {
+    decode_packet(&mut BitReader::new(transmission))
 }
 
-fn decode_bits_to_literal(input: &str) -> Option<(SubPacket, usize)> {
-    let mut ss = String::new();
+fn decode_packet(reader: &mut BitReader) -> Result<Packet, ParseError> {
+    let version = reader.read(3)? as u8;
+
+    let start = reader.position();
+    let payload = match reader.read(3)? {
+        4 => decode_literal(reader)?,
+        type_id => {
+            let operator = Operator::from_type_id(type_id)
+                .ok_or_else(|| reader.error_at(start, format!("invalid type id {}", type_id)))?;
+
+            decode_operator(operator, reader)?
+        }
+    };
 
-    let mut len = 0usize;
+    Ok(Packet { version, payload })
+}
 
-    for i in (0..input.len()).step_by(5) {
-        let token = &input[i..(i + 5) % input.len()];
+fn decode_literal(reader: &mut BitReader) -> Result<Payload, ParseError> {
+    let start = reader.position();
+    let mut value = 0u64;
 
-        ss += &token.chars().skip(1).take(4).collect::<String>();
+    loop {
+        let group = reader.read(5)?;
 
-        len += token.len();
+        value = value
+            .checked_mul(16)
+            .ok_or_else(|| reader.error_at(start, "literal does not fit in 64 bits"))?
+            + (group & 0b1111);
 
-        if token.chars().nth(0) == Some('0') {
+        if group & 0b10000 == 0 {
             break;
         }
     }
 
-    Some((SubPacket::LITERAL(i32::from_str_radix(&ss, 2).ok()?), len))
+    Ok(Payload::Literal(value))
 }
 
-fn decode_bits_to_operator(input: &str) -> Option<(SubPacket, usize)> {
-    let id = if input.chars().nth(0)? == '0' {
-        LenghtTypeId::TotalLengthInBits(i32::from_str_radix(&input[1..16], 2).ok()?)
+fn decode_operator(operator: Operator, reader: &mut BitReader) -> Result<Payload, ParseError> {
+    let start = reader.position();
+
+    let length_type = if reader.read(1)? == 0 {
+        LengthType::Bits
     } else {
-        LenghtTypeId::TotalSubPackets(i32::from_str_radix(&input[1..12], 2).ok()?)
+        LengthType::SubPackets
     };
 
-    match id {
-        LenghtTypeId::TotalLengthInBits(_) => todo!(),
-        LenghtTypeId::TotalSubPackets(_) => todo!(),
-        _ => None,
+    let mut packets = vec![];
+
+    match length_type {
+        LengthType::Bits => {
+            let length = reader.read(15)? as usize;
+            let end = reader.position() + length;
+
+            while reader.position() < end {
+                packets.push(decode_packet(reader)?);
+            }
+            if reader.position() > end {
+                return Err(reader.error_at(start, "sub-packets overrun their length"));
+            }
+        }
+        LengthType::SubPackets => {
+            for _ in 0..reader.read(11)? {
+                packets.push(decode_packet(reader)?);
+            }
+        }
+    }
+
+    // Comparisons take exactly two operands, everything else at least one
+    if packets.is_empty() || (operator.is_comparison() && packets.len() != 2) {
+        return Err(reader.error_at(
+            start,
+            format!("{:?} can not take {} operands", operator, packets.len()),
+        ));
     }
 
-    for i in (0..input.len()).step_by(5) {}
-    // if input.chars().nth(0)? == '0' {
-    //     Some(SubPacket::OPERATOR(
-    //         ,
-    //         decode_bits_to_sub_packets(&input[16..]),
-    //     ))
-    // } else if input.chars().nth(0)? == '1' {
-    //     Some(SubPacket::OPERATOR(
-    //         ),
-    //         decode_bits_to_sub_packets(&input[12..]),
-    //     ))
-    // } else {
-    //     None
-    // }
+    Ok(Payload::Operator(operator, length_type, packets))
+}
+
+/// Encode `packet` as a hexadecimal transmission. Operator packets keep their `LengthType`, the length or
+/// count written is taken from the sub-packets.
+pub fn encode(packet: &Packet) -> Result<String, EncodeError> {
+    let mut writer = BitWriter::default();
+
+    encode_packet(packet, &mut writer)?;
+    debug_assert_eq!(writer.position(), packet.encoded_len());
+
+    Ok(writer.finish())
+}
+
+fn encode_packet(packet: &Packet, writer: &mut BitWriter) -> Result<(), EncodeError> {
+    if packet.version > 7 {
+        return Err(EncodeError::Version(packet.version));
+    }
+    writer.write(packet.version as u64, 3);
+
+    match &packet.payload {
+        Payload::Literal(value) => {
+            writer.write(4, 3);
+
+            let groups = literal_groups(*value);
+            for group in (0..groups).rev() {
+                let more = if group > 0 { 0b10000 } else { 0 };
+                writer.write(more | ((value >> (4 * group)) & 0b1111), 5);
+            }
+        }
+        Payload::Operator(operator, length_type, packets) => {
+            writer.write(operator.type_id(), 3);
+
+            match length_type {
+                LengthType::Bits => {
+                    let len = packets.iter().map(Packet::encoded_len).sum::<usize>();
+                    if len > MAX_LENGTH_IN_BITS {
+                        return Err(EncodeError::SubPacketsTooLong(len));
+                    }
+                    writer.write(0, 1);
+                    writer.write(len as u64, 15);
+                }
+                LengthType::SubPackets => {
+                    if packets.len() > MAX_SUB_PACKETS {
+                        return Err(EncodeError::TooManySubPackets(packets.len()));
+                    }
+                    writer.write(1, 1);
+                    writer.write(packets.len() as u64, 11);
+                }
+            }
+
+            for packet in packets {
+                encode_packet(packet, writer)?;
+            }
+        }
+    }
+    Ok(())
 }
 
-fn part1() {
-    println!("Day 16 part 1 : ");
+struct PacketDecoder {
+    packet: Packet,
 }
 
-fn part2() {
-    println!("Day 16 part 2 : ");
+impl Solution for PacketDecoder {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let transmission = input.trim();
+
+        if let Some(position) = transmission.find(|c: char| !c.is_ascii_hexdigit()) {
+            return Err(
+                ParseError::new(position + 1, "expected a hexadecimal transmission").on_line(1),
+            );
+        }
+
+        let packet = decode(transmission).map_err(|e| e.on_line(1))?;
+
+        Ok(PacketDecoder { packet })
+    }
+
+    fn part1(&self) -> Answer {
+        self.packet.version_sum().into()
+    }
+
+    fn part2(&self) -> Answer {
+        match self.packet.evaluate() {
+            Some(value) => value.into(),
+            None => "does not fit in 64 bits".into(),
+        }
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<PacketDecoder>(16, "Packet Decoder", env!("CARGO_MANIFEST_DIR"));
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{decode_bits_to_packet, decode_hex_to_binary, LenghtTypeId, Packet, SubPacket};
+    use proptest::prelude::*;
+
+    use crate::{decode, encode, EncodeError, LengthType, Operator, Packet, Payload};
+
+    fn operators() -> impl Strategy<Value = Operator> {
+        prop_oneof![
+            Just(Operator::Sum),
+            Just(Operator::Product),
+            Just(Operator::Minimum),
+            Just(Operator::Maximum),
+        ]
+    }
+
+    fn comparisons() -> impl Strategy<Value = Operator> {
+        prop_oneof![
+            Just(Operator::GreaterThan),
+            Just(Operator::LessThan),
+            Just(Operator::EqualTo),
+        ]
+    }
+
+    fn length_types() -> impl Strategy<Value = Option<LengthType>> {
+        prop_oneof![
+            Just(None),
+            Just(Some(LengthType::Bits)),
+            Just(Some(LengthType::SubPackets)),
+        ]
+    }
+
+    /// Packets the decoder accepts, comparisons always get two operands.
+    fn packets() -> impl Strategy<Value = Packet> {
+        let literal =
+            (0..8u8, any::<u64>()).prop_map(|(version, value)| Packet::literal(version, value));
+
+        literal.prop_recursive(4, 64, 6, |inner| {
+            prop_oneof![
+                (
+                    0..8u8,
+                    operators(),
+                    length_types(),
+                    prop::collection::vec(inner.clone(), 1..6)
+                )
+                    .prop_map(|(version, operator, length_type, packets)| {
+                        Packet::operator(version, operator, length_type, packets)
+                    }),
+                (0..8u8, comparisons(), length_types(), inner.clone(), inner).prop_map(
+                    |(version, operator, length_type, lhs, rhs)| {
+                        Packet::operator(version, operator, length_type, vec![lhs, rhs])
+                    }
+                ),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip_test1(packet in packets()) {
+            let hex = encode(&packet).unwrap();
+
+            prop_assert_eq!(hex.len(), packet.encoded_len().div_ceil(4));
+            prop_assert_eq!(decode(&hex), Ok(packet));
+        }
+    }
 
     #[test]
     fn part1_test1() {
-        let bits = decode_hex_to_binary("D2FE28");
-        assert_eq!(bits, "110100101111111000101000");
-
-        let packet = decode_bits_to_packet(&bits);
+        let packet = decode("D2FE28");
 
         assert_eq!(
             packet,
-            Some(Packet {
+            Ok(Packet {
                 version: 6,
-                subpackets: vec![SubPacket::LITERAL(2021)]
+                payload: Payload::Literal(2021)
             })
         );
     }
 
     #[test]
     fn part1_test2() {
-        let bits = decode_hex_to_binary("38006F45291200");
-        assert_eq!(
-            bits,
-            "00111000000000000110111101000101001010010001001000000000"
-        );
-
-        let packet = decode_bits_to_packet(&bits);
+        let packet = decode("38006F45291200");
 
         assert_eq!(
             packet,
-            Some(Packet {
+            Ok(Packet {
                 version: 1,
-                subpackets: vec![SubPacket::OPERATOR(
-                    LenghtTypeId::TotalLengthInBits(27),
-                    vec![SubPacket::LITERAL(10), SubPacket::LITERAL(20),]
-                )]
+                payload: Payload::Operator(
+                    Operator::LessThan,
+                    LengthType::Bits,
+                    vec![
+                        Packet {
+                            version: 6,
+                            payload: Payload::Literal(10)
+                        },
+                        Packet {
+                            version: 2,
+                            payload: Payload::Literal(20)
+                        },
+                    ]
+                )
             })
         );
     }
+
+    #[test]
+    fn part1_test3() {
+        let packet = decode("EE00D40C823060").unwrap();
+
+        assert_eq!(packet.version, 7);
+        match packet.payload {
+            Payload::Operator(Operator::Maximum, LengthType::SubPackets, packets) => {
+                assert_eq!(
+                    packets.iter().map(|p| &p.payload).collect::<Vec<_>>(),
+                    vec![
+                        &Payload::Literal(1),
+                        &Payload::Literal(2),
+                        &Payload::Literal(3)
+                    ]
+                );
+            }
+            payload => panic!("unexpected payload {:?}", payload),
+        }
+
+        assert_eq!(decode("8A004A801A8002F478").unwrap().version_sum(), 16);
+        assert_eq!(
+            decode("620080001611562C8802118E34").unwrap().version_sum(),
+            12
+        );
+        assert_eq!(
+            decode("C0015000016115A2E0802F182340")
+                .unwrap()
+                .version_sum(),
+            23
+        );
+        assert_eq!(
+            decode("A0016C880162017C3686B18A3D4780")
+                .unwrap()
+                .version_sum(),
+            31
+        );
+
+        assert_eq!(decode("8A004").unwrap_err().column, 5);
+        assert_eq!(decode("E").unwrap_err().column, 1);
+        assert_eq!(decode("13FFFFFFFFFFFFFFFFFFFDE").unwrap_err().column, 2);
+    }
+
+    #[test]
+    fn part2_test1() {
+        assert_eq!(decode("C200B40A82").unwrap().evaluate(), Some(3));
+        assert_eq!(decode("04005AC33890").unwrap().evaluate(), Some(54));
+        assert_eq!(decode("880086C3E88112").unwrap().evaluate(), Some(7));
+        assert_eq!(decode("CE00C43D881120").unwrap().evaluate(), Some(9));
+        assert_eq!(decode("D8005AC2A8F0").unwrap().evaluate(), Some(1));
+        assert_eq!(decode("F600BC2D8F").unwrap().evaluate(), Some(0));
+        assert_eq!(decode("9C005AC2F8F0").unwrap().evaluate(), Some(0));
+        assert_eq!(
+            decode("9C0141080250320F1802104A08").unwrap().evaluate(),
+            Some(1)
+        );
+
+        let max = || Packet::literal(0, u64::MAX);
+        let sum = Packet::operator(0, Operator::Sum, None, vec![max(), max()]);
+        let product = Packet::operator(0, Operator::Product, None, vec![max(), max()]);
+        let compared = Packet::operator(0, Operator::EqualTo, None, vec![max(), sum.clone()]);
+        assert_eq!(sum.evaluate(), None);
+        assert_eq!(product.evaluate(), None);
+        assert_eq!(compared.evaluate(), None);
+        assert_eq!(
+            Packet::operator(0, Operator::Maximum, None, vec![max(), max()]).evaluate(),
+            Some(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn encode_test1() {
+        assert_eq!(encode(&Packet::literal(6, 2021)), Ok("D2FE28".to_string()));
+
+        let packet = Packet::operator(
+            1,
+            Operator::LessThan,
+            Some(LengthType::Bits),
+            vec![Packet::literal(6, 10), Packet::literal(2, 20)],
+        );
+        assert_eq!(encode(&packet), Ok("38006F4529120".to_string()));
+        assert_eq!(decode("38006F45291200"), Ok(packet));
+
+        let packet = Packet::operator(
+            7,
+            Operator::Maximum,
+            None,
+            vec![
+                Packet::literal(2, 1),
+                Packet::literal(4, 2),
+                Packet::literal(1, 3),
+            ],
+        );
+        assert_eq!(encode(&packet), Ok("EE00D40C82306".to_string()));
+
+        assert_eq!(encode(&Packet::literal(8, 1)), Err(EncodeError::Version(8)));
+    }
+
+    #[test]
+    fn display_test1() {
+        let packet = decode("9C0141080250320F1802104A08").unwrap();
+
+        assert_eq!(packet.to_string(), "(eq (sum 1 3) (product 2 2))");
+        assert_eq!(
+            format!("{:#}", packet),
+            "(eq v4\n  (sum v2\n    (literal v2 1)\n    (literal v4 3))\n  (product v6\n    (literal v0 2)\n    (literal v2 2)))"
+        );
+    }
 }
@@ -1,34 +1,39 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::str::FromStr;
 
+use common::{parse_lines, Answer, ParseError, Registry, Solution};
+
 struct Movement {
     direction: String,
     count: i64,
 }
 
-#[derive(Debug)]
-struct ParseMovementError {}
-
 impl FromStr for Movement {
-    type Err = ParseMovementError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let tokens: Vec<&str> = s.split(' ').collect();
+        let (direction, count) = s
+            .split_once(' ')
+            .ok_or_else(|| ParseError::new(1, "expected '<direction> <count>'"))?;
+
+        if !matches!(direction, "forward" | "up" | "down") {
+            return Err(ParseError::at(
+                s,
+                direction,
+                format!("invalid direction '{}'", direction),
+            ));
+        }
 
         Ok(Movement {
-            direction: tokens[0].to_string(),
-            count: tokens[1].parse::<i64>().unwrap(),
+            direction: direction.to_string(),
+            count: count.parse::<i64>().map_err(|e| {
+                ParseError::at(s, count, format!("invalid count '{}' : {}", count, e))
+            })?,
         })
     }
 }
 
-fn parse_movements(name: &str) -> Vec<Movement> {
-    BufReader::new(File::open(name).unwrap())
-        .lines()
-        .map(|line| line.unwrap().parse())
-        .map(|option| option.unwrap())
-        .collect()
+fn parse_movements(input: &str) -> Result<Vec<Movement>, ParseError> {
+    parse_lines(input, |line| line.parse())
 }
 
 struct PositionAndAim {
@@ -68,36 +73,36 @@ impl PositionAndAim {
     }
 }
 
-fn part1() {
-    let movements = parse_movements("days/day2/input.txt");
+struct Dive {
+    movements: Vec<Movement>,
+}
 
-    let mut position = PositionAndAim::new();
-    for movement in movements {
-        position.apply_one(&movement);
+impl Solution for Dive {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Dive {
+            movements: parse_movements(input)?,
+        })
     }
 
-    println!(
-        "Day 2 part 1 : Horizontal * Depth : {}",
-        position.horizontal * position.depth
-    );
-}
-
-fn part2() {
-    let movements = parse_movements("days/day2/input.txt");
+    fn part1(&self) -> Answer {
+        let mut position = PositionAndAim::new();
+        for movement in &self.movements {
+            position.apply_one(movement);
+        }
 
-    let mut position = PositionAndAim::new();
-    for movement in movements {
-        position.apply_two(&movement);
+        (position.horizontal * position.depth).into()
     }
 
-    println!(
-        "Day 2 part 2 : Horizontal * Depth : {}",
-        position.horizontal * position.depth
-    );
+    fn part2(&self) -> Answer {
+        let mut position = PositionAndAim::new();
+        for movement in &self.movements {
+            position.apply_two(movement);
+        }
+
+        (position.horizontal * position.depth).into()
+    }
 }
 
-pub fn run()
-{
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<Dive>(2, "Dive!", env!("CARGO_MANIFEST_DIR"));
 }
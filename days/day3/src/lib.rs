@@ -1,4 +1,4 @@
-use common::import;
+use common::{parse_lines, Answer, ParseError, Registry, Solution};
 
 fn rank(values: &[String], position: usize) -> i32 {
     values
@@ -45,8 +45,7 @@ fn bit_criteria(mut input: Vec<String>, ch: char) -> String {
 
         input.retain(|a| {
             let c = a.chars().nth(position).unwrap();
-            (r == 0 && c == ch)
-                || (r > 0 && c == ch)
+            (r >= 0 && c == ch)
                 || (r < 0
                     && c == if ch == '1' {
                         '0'
@@ -62,6 +61,63 @@ fn bit_criteria(mut input: Vec<String>, ch: char) -> String {
     input[0].clone()
 }
 
+struct BinaryDiagnostic {
+    report: Vec<String>,
+    width: usize,
+}
+
+impl Solution for BinaryDiagnostic {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let width = input.lines().next().map(|l| l.len()).unwrap_or_default();
+
+        let report = parse_lines(input, |line| {
+            if let Some(position) = line.find(|c| c != '0' && c != '1') {
+                Err(ParseError::new(position + 1, "expected a binary number"))
+            } else if line.len() != width {
+                Err(ParseError::new(
+                    1,
+                    format!("expected {} bits, found {}", width, line.len()),
+                ))
+            } else {
+                Ok(line.to_string())
+            }
+        })?;
+
+        if report.is_empty() {
+            return Err(ParseError::new(1, "empty report"));
+        }
+        // The rates are multiplied, 31 bits each keeps the product within an i64
+        if width == 0 || width > 31 {
+            return Err(
+                ParseError::new(1, format!("expected 1 to 31 bits, found {}", width)).on_line(1),
+            );
+        }
+
+        Ok(BinaryDiagnostic { report, width })
+    }
+
+    fn part1(&self) -> Answer {
+        let gamma = (0..self.width).fold("".to_string(), |mut g, position| {
+            g.push(to_char(rank(&self.report, position)));
+            g
+        });
+        let epsilon = invert(&gamma);
+
+        multiply_radix_2(&gamma, &epsilon).into()
+    }
+
+    fn part2(&self) -> Answer {
+        let o = bit_criteria(self.report.clone(), '1');
+        let c = bit_criteria(self.report.clone(), '0');
+
+        multiply_radix_2(&o, &c).into()
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register::<BinaryDiagnostic>(3, "Binary Diagnostic", env!("CARGO_MANIFEST_DIR"));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,10 +129,7 @@ mod tests {
             "11001", "00010", "01010",
         ];
 
-        let strings = input
-            .iter()
-            .map(|&s: &&str| s.into())
-            .collect::<Vec<_>>();
+        let strings = input.iter().map(|&s: &&str| s.into()).collect::<Vec<_>>();
 
         assert!(rank(&strings, 0) > 0);
         assert!(rank(&strings, 1) < 0);
@@ -99,39 +152,17 @@ mod tests {
         assert_eq!(o, "10111");
         let c = bit_criteria(strings.clone(), '0');
         assert_eq!(c, "01010");
-    }
-}
 
-fn part1() {
-    let input = import("days/day3/input.txt");
-
-    let gamma = (0..12).fold("".to_string(), |mut g, position| {
-        g.push(to_char(rank(&input, position)));
-        g
-    });
-    let epsilon = invert(&gamma);
-    let power = multiply_radix_2(&gamma, &epsilon);
-
-    println!(
-        "Day 3 part 1 : Gamma: {} Epsilon: {} Power: {}",
-        gamma, epsilon, power
-    );
-}
-
-fn part2() {
-    let input = import("days/day3/input.txt");
-
-    let o = bit_criteria(input.clone(), '1');
-    let c = bit_criteria(input.clone(), '0');
-    let l = multiply_radix_2(&o, &c);
-
-    println!(
-        "Day 3 part 2 : Oxygen rating: {} CO2 scrubber rating: {} Life support rating: {}",
-        o, c, l
-    );
-}
-
-pub fn run() {
-    part1();
-    part2();
+        let error = |input: &str| BinaryDiagnostic::parse(input).err().map(|e| e.to_string());
+        assert_eq!(error(""), Some("empty report".into()));
+        assert_eq!(
+            error("\n"),
+            Some("1:1: expected 1 to 31 bits, found 0".into())
+        );
+        assert_eq!(
+            error(&"1".repeat(32)),
+            Some("1:1: expected 1 to 31 bits, found 32".into())
+        );
+        assert!(error("0").is_none());
+    }
 }
@@ -1,7 +1,8 @@
 use std::collections::HashSet;
-use std::fs;
 
-#[derive(Debug)]
+use common::{parse_list, Answer, ParseError, Registry, Solution};
+
+#[derive(Debug, Clone)]
 struct Loc {
     n: i32,
     m: bool,
@@ -13,7 +14,7 @@ impl Loc {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Board {
     rows: Vec<Vec<Loc>>,
     columns: Vec<Vec<Loc>>,
@@ -112,28 +113,40 @@ impl Board {
     }
 }
 
-fn draws() -> Vec<i32> {
-    vec![
-        31, 88, 35, 24, 46, 48, 95, 42, 18, 43, 71, 32, 92, 62, 97, 63, 50, 2, 60, 58, 74, 66, 15,
-        87, 57, 34, 14, 3, 54, 93, 75, 22, 45, 10, 56, 12, 83, 30, 8, 76, 1, 78, 82, 39, 98, 37,
-        19, 26, 81, 64, 55, 41, 16, 4, 72, 5, 52, 80, 84, 67, 21, 86, 23, 91, 0, 68, 36, 13, 44,
-        20, 69, 40, 90, 96, 27, 77, 38, 49, 94, 47, 9, 65, 28, 59, 79, 6, 29, 61, 53, 11, 17, 73,
-        99, 25, 89, 51, 7, 33, 85, 70,
-    ]
-}
-
-fn boards() -> Vec<Board> {
-    let lines = fs::read_to_string("days/day4/input.txt").unwrap();
+/// Parse the boards, `first_line` is the line number of the first line of `input`
+fn boards(input: &[&str], first_line: usize) -> Result<Vec<Board>, ParseError> {
+    let mut boards = vec![];
+    let mut numbers = vec![];
+
+    // An empty line ends a board, so does the end of the input
+    for (n, &line) in input
+        .iter()
+        .chain(std::iter::once(&""))
+        .enumerate()
+        .map(|(n, line)| (n + first_line - 1, line))
+    {
+        if line.trim().is_empty() {
+            if !numbers.is_empty() {
+                if numbers.len() != 25 {
+                    return Err(ParseError::new(
+                        1,
+                        format!("expected a board of 5x5 numbers, found {}", numbers.len()),
+                    )
+                    .on_line(n));
+                }
+                boards.push(Board::new(&numbers));
+                numbers.clear();
+            }
+            continue;
+        }
 
-    lines
-        .split("\n\n")
-        .map(|c| {
-            c.split_whitespace()
-                .map(|t| t.parse().unwrap())
-                .collect::<Vec<i32>>()
-        })
-        .map(|n| Board::new(&n))
-        .collect()
+        for token in line.split_whitespace() {
+            numbers.push(token.parse::<i32>().map_err(|_| {
+                ParseError::at(line, token, format!("invalid number '{}'", token)).on_line(n + 1)
+            })?);
+        }
+    }
+    Ok(boards)
 }
 
 fn play_boards(draws: &[i32], boards: &mut [Board]) {
@@ -150,37 +163,49 @@ fn play_boards(draws: &[i32], boards: &mut [Board]) {
     }
 }
 
-pub fn part1() {
-    let mut boards = boards();
+struct GiantSquid {
+    draws: Vec<i32>,
+    boards: Vec<Board>,
+}
+
+impl Solution for GiantSquid {
+    /// The first line holds the draws, the boards follow after an empty line
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let lines = input.lines().collect::<Vec<_>>();
 
-    play_boards(&draws(), &mut boards);
+        let (draws, boards_lines) = lines
+            .split_first()
+            .ok_or_else(|| ParseError::new(1, "missing draws").on_line(1))?;
 
-    boards.sort_by(|a, b| a.round.cmp(&b.round));
+        Ok(GiantSquid {
+            draws: parse_list(draws, ',').map_err(|e| e.on_line(1))?,
+            boards: boards(boards_lines, 2)?,
+        })
+    }
 
-    println!(
-        "Day 4 part 1 : Board won in round {} with score {}",
-        boards[0].round,
-        boards[0].score()
-    );
-}
+    fn part1(&self) -> Answer {
+        let mut boards = self.boards.clone();
 
-pub fn part2() {
-    let mut boards = boards();
+        play_boards(&self.draws, &mut boards);
 
-    play_boards(&draws(), &mut boards);
+        boards.sort_by_key(|b| b.round);
 
-    boards.sort_by(|a, b| b.round.cmp(&a.round));
+        boards[0].score().into()
+    }
 
-    println!(
-        "Day 4 part 2 : Last board complete in round {} with score {}",
-        boards[0].round,
-        boards[0].score()
-    );
+    fn part2(&self) -> Answer {
+        let mut boards = self.boards.clone();
+
+        play_boards(&self.draws, &mut boards);
+
+        boards.sort_by_key(|b| std::cmp::Reverse(b.round));
+
+        boards[0].score().into()
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<GiantSquid>(4, "Giant Squid", env!("CARGO_MANIFEST_DIR"));
 }
 
 #[cfg(test)]
@@ -1,35 +1,15 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-struct V {
-    x: i32,
-    y: i32,
-}
-
-impl V {
-    fn new(x: i32, y: i32) -> V {
-        V { x, y }
-    }
-}
-
-impl FromStr for V {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let tokens: Vec<&str> = s.split(',').collect();
+use common::{
+    animation::{Cell, GraphicRendition},
+    Answer, Bounds, Grid, ParseError, Point2, Registry, Solution,
+};
 
-        Ok(V {
-            x: tokens[0].parse().unwrap(),
-            y: tokens[1].parse().unwrap(),
-        })
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Line {
-    begin: V,
-    end: V,
+    begin: Point2<i32>,
+    end: Point2<i32>,
 }
 
 fn step(b: i32, e: i32) -> i32 {
@@ -62,7 +42,7 @@ impl Line {
         !self.is_horizontal() && !self.is_vertical()
     }
 
-    fn points(&self) -> Vec<V> {
+    fn points(&self) -> Vec<Point2<i32>> {
         let mut r = vec![];
 
         let x_step = step(self.begin.x, self.end.x);
@@ -71,7 +51,7 @@ impl Line {
         let mut x = self.begin.x;
         let mut y = self.begin.y;
         while between(x, self.begin.x, self.end.x) && between(y, self.begin.y, self.end.y) {
-            r.push(V { x, y });
+            r.push(Point2::new(x, y));
             x += x_step;
             y += y_step;
         }
@@ -80,24 +60,38 @@ impl Line {
 }
 
 impl FromStr for Line {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let tokens: Vec<&str> = s.split(" -> ").collect();
+        let (begin, end) = s
+            .split_once(" -> ")
+            .ok_or_else(|| ParseError::new(1, "expected 'x1,y1 -> x2,y2'"))?;
+
+        // Report errors in the coordinates relative to the line
+        let parse = |token: &str| {
+            token.parse::<Point2<i32>>().map_err(|e| ParseError {
+                column: e.column + token.as_ptr() as usize - s.as_ptr() as usize,
+                ..e
+            })
+        };
 
         Ok(Line {
-            begin: tokens[0].parse().unwrap(),
-            end: tokens[1].parse().unwrap(),
+            begin: parse(begin)?,
+            end: parse(end)?,
         })
     }
 }
 
-fn parse_lines(input: &[String]) -> Vec<Line> {
-    input.iter().map(|line| line.parse().unwrap()).collect()
+fn parse_lines(input: &[String]) -> Result<Vec<Line>, ParseError> {
+    input
+        .iter()
+        .enumerate()
+        .map(|(n, line)| line.parse().map_err(|e: ParseError| e.on_line(n + 1)))
+        .collect()
 }
 
-fn build_map(lines: &Vec<Line>) -> HashMap<V, i32> {
-    let mut result = HashMap::<V, i32>::new();
+fn build_map(lines: &Vec<Line>) -> HashMap<Point2<i32>, i32> {
+    let mut result = HashMap::<Point2<i32>, i32>::new();
 
     for line in lines {
         for point in line.points() {
@@ -107,49 +101,82 @@ fn build_map(lines: &Vec<Line>) -> HashMap<V, i32> {
     result
 }
 
-fn print_map(top_left: &V, bottom_right: &V, map: &HashMap<V, i32>) {
-    for y in top_left.y..=bottom_right.y {
-        for x in top_left.x..=bottom_right.x {
-            let position = V::new(x, y);
+pub struct HydrothermalVenture {
+    lines: Vec<Line>,
+}
 
-            if let Some(count) = map.get(&position) {
-                print!("{}", count);
-            } else {
-                print!(".");
+impl HydrothermalVenture {
+    /// The number of vent lines over every point after adding the lines one by one, diagonal lines
+    /// included when `diagonals` is set. The grid starts at the top left point any line covers. Every
+    /// frame is a copy of the whole grid, so collecting them takes memory for each line.
+    pub fn accumulation(&self, diagonals: bool) -> impl Iterator<Item = Grid<u32>> + '_ {
+        let lines = self
+            .lines
+            .iter()
+            .filter(move |line| diagonals || !line.is_diagonal())
+            .collect::<Vec<_>>();
+
+        let bounds = lines
+            .iter()
+            .flat_map(|line| [line.begin, line.end])
+            .collect::<Bounds<_>>();
+        let top_left = bounds.min().unwrap_or(Point2::new(0, 0));
+        let mut counts = Grid::from_fn(bounds.width() as usize, bounds.height() as usize, |_| 0);
+
+        lines.into_iter().map(move |line| {
+            for point in line.points() {
+                let position = point - top_left;
+                counts[&Point2::new(position.x as i64, position.y as i64)] += 1;
             }
-        }
-        println!();
+            counts.clone()
+        })
     }
 }
 
-fn part1() {
-    let lines = parse_lines(&common::import("days/day5/input.txt"))
-        .into_iter()
-        .filter(|line| !line.is_diagonal())
-        .collect();
+/// Points covered once in plain digits, overlaps in bold red.
+pub fn vent_cell(count: &u32) -> Cell {
+    const OVERLAP: &[GraphicRendition] = &[
+        GraphicRendition::HighIntensity,
+        GraphicRendition::RedForeground,
+    ];
+
+    match (*count, char::from_digit(*count, 10)) {
+        (0, _) => Cell::plain('.'),
+        (1, _) => Cell::plain('1'),
+        (_, Some(digit)) => Cell::new(digit, OVERLAP),
+        (_, None) => Cell::new('+', OVERLAP),
+    }
+}
 
-    let map = build_map(&lines);
+impl Solution for HydrothermalVenture {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(HydrothermalVenture {
+            lines: parse_lines(&input.lines().map(|s| s.to_string()).collect::<Vec<_>>())?,
+        })
+    }
 
-    println!(
-        "Day 5 part 1 : {} positions with 2 or more lines",
-        map.values().filter(|&c| c >= &2).count()
-    );
-}
+    fn part1(&self) -> Answer {
+        let lines = self
+            .lines
+            .iter()
+            .filter(|line| !line.is_diagonal())
+            .cloned()
+            .collect();
+
+        let map = build_map(&lines);
 
-fn part2() {
-    let lines = parse_lines(&common::import("days/day5/input.txt"));
+        map.values().filter(|&c| c >= &2).count().into()
+    }
 
-    let map = build_map(&lines);
+    fn part2(&self) -> Answer {
+        let map = build_map(&self.lines);
 
-    println!(
-        "Day 5 part 2 : {} positions with 2 or more lines",
-        map.values().filter(|&c| c >= &2).count()
-    );
+        map.values().filter(|&c| c >= &2).count().into()
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<HydrothermalVenture>(5, "Hydrothermal Venture", env!("CARGO_MANIFEST_DIR"));
 }
 
 #[cfg(test)]
@@ -175,16 +202,31 @@ mod tests {
         .collect::<Vec<_>>();
 
         let lines = parse_lines(&input)
+            .unwrap()
             .into_iter()
             .filter(|line| !line.is_diagonal())
             .collect();
 
         let map = build_map(&lines);
-        // print_map(&V::new(0, 0), &V::new(9, 9), &map);
 
-        assert_eq!(map[&V::new(0, 9)], 2);
-        assert_eq!(map[&V::new(1, 9)], 2);
-        assert_eq!(map[&V::new(2, 9)], 2);
-        assert_eq!(map[&V::new(3, 9)], 1);
+        assert_eq!(map[&Point2::new(0, 9)], 2);
+        assert_eq!(map[&Point2::new(1, 9)], 2);
+        assert_eq!(map[&Point2::new(2, 9)], 2);
+        assert_eq!(map[&Point2::new(3, 9)], 1);
+
+        let vents = HydrothermalVenture {
+            lines: parse_lines(&input).unwrap(),
+        };
+
+        let frames = vents.accumulation(false).collect::<Vec<_>>();
+        assert_eq!(frames.len(), 6);
+        assert_eq!((frames[5].width(), frames[5].height()), (10, 10));
+        assert_eq!(frames[0][&Point2::new(0, 9)], 1);
+        assert_eq!(frames[5][&Point2::new(0, 9)], 2);
+        assert_eq!(frames[5].values().filter(|&&c| c >= 2).count(), 5);
+
+        let last = vents.accumulation(true).last().unwrap();
+        assert_eq!(last.values().filter(|&&c| c >= 2).count(), 12);
+        assert_eq!(vent_cell(&2).symbol, '2');
     }
 }
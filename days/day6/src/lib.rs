@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use common::{parse_list, Answer, ParseError, Registry, Solution};
+
 fn generation(v: &HashMap<i64, i64>) -> HashMap<i64, i64> {
     let mut r = HashMap::<i64, i64>::new();
 
@@ -27,50 +29,48 @@ fn make_map(fish: &Vec<i64>) -> HashMap<i64, i64> {
     r
 }
 
-fn input() -> Vec<i64> {
-    vec![
-        1, 1, 1, 2, 1, 5, 1, 1, 2, 1, 4, 1, 4, 1, 1, 1, 1, 1, 1, 4, 1, 1, 1, 1, 4, 1, 1, 5, 1, 3,
-        1, 2, 1, 1, 1, 2, 1, 1, 1, 4, 1, 1, 3, 1, 5, 1, 1, 1, 1, 3, 5, 5, 2, 1, 1, 1, 2, 1, 1, 1,
-        1, 1, 1, 1, 1, 5, 4, 1, 1, 1, 1, 1, 3, 1, 1, 2, 4, 4, 1, 1, 1, 1, 1, 1, 3, 1, 1, 1, 1, 5,
-        1, 3, 1, 5, 1, 2, 1, 1, 5, 1, 1, 1, 5, 3, 3, 1, 4, 1, 3, 1, 3, 1, 1, 1, 1, 3, 1, 4, 1, 1,
-        1, 1, 1, 2, 1, 1, 1, 4, 2, 1, 1, 5, 1, 1, 1, 2, 1, 1, 1, 1, 1, 1, 1, 1, 2, 1, 1, 1, 1, 1,
-        5, 1, 1, 1, 1, 3, 1, 1, 1, 1, 1, 3, 4, 1, 2, 1, 3, 2, 1, 1, 2, 1, 1, 1, 1, 4, 1, 1, 1, 1,
-        4, 1, 1, 1, 1, 1, 2, 1, 1, 4, 1, 1, 1, 5, 3, 2, 2, 1, 1, 3, 1, 5, 1, 5, 1, 1, 1, 1, 1, 5,
-        1, 4, 1, 2, 1, 1, 1, 1, 2, 1, 3, 1, 1, 1, 1, 1, 1, 2, 1, 1, 1, 3, 1, 4, 3, 1, 4, 1, 3, 2,
-        1, 1, 1, 1, 1, 3, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 1, 5, 1, 1, 1, 1, 2, 1, 1, 1, 3, 5, 1,
-        1, 1, 1, 5, 1, 1, 2, 1, 2, 4, 2, 2, 1, 1, 1, 5, 2, 1, 1, 5, 1, 1, 1, 1, 5, 1, 1, 1, 2, 1,
-    ]
+struct Lanternfish {
+    fish: HashMap<i64, i64>,
 }
 
-fn part1() {
-    let mut fish = make_map(&input());
-
-    for _ in 0..80 {
-        fish = generation(&fish);
-    }
+impl Lanternfish {
+    fn after(&self, days: usize) -> i64 {
+        let mut fish = self.fish.clone();
 
-    println!(
-        "Day 6 part 1 : After 80 days there are {} lanterfish",
+        for _ in 0..days {
+            fish = generation(&fish);
+        }
         fish.values().sum::<i64>()
-    );
+    }
 }
 
-fn part2() {
-    let mut fish = make_map(&input());
+impl Solution for Lanternfish {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let line = input.trim_end();
+        let fish = parse_list::<i64>(line, ',').map_err(|e| e.on_line(1))?;
+
+        if let Some(position) = fish.iter().position(|f| !(0..=8).contains(f)) {
+            let token = line.split(',').nth(position).unwrap_or_default();
 
-    for _ in 0..256 {
-        fish = generation(&fish);
+            return Err(ParseError::at(line, token, "timer should be in range 0 ..= 8").on_line(1));
+        }
+
+        Ok(Lanternfish {
+            fish: make_map(&fish),
+        })
     }
 
-    println!(
-        "Day 6 part 2 : After 256 days there are {} lanterfish",
-        fish.values().sum::<i64>()
-    );
+    fn part1(&self) -> Answer {
+        self.after(80).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.after(256).into()
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<Lanternfish>(6, "Lanternfish", env!("CARGO_MANIFEST_DIR"));
 }
 
 #[cfg(test)]
@@ -85,6 +85,6 @@ mod tests {
             //print!("{} --> {:?}\n", d, fish);
             fish = generation(&fish);
         }
-        assert_eq!(fish.values().fold(0, |a, &v| a + v), 5934);
+        assert_eq!(fish.values().sum::<i64>(), 5934);
     }
 }
@@ -1,10 +1,6 @@
+use common::{parse_lines, Answer, ParseError, Registry, Solution};
 use itertools::Itertools;
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufRead, BufReader},
-    str::FromStr,
-};
+use std::{collections::HashMap, str::FromStr};
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, PartialOrd, Ord, Eq, PartialEq)]
@@ -129,39 +125,43 @@ impl Entry {
     }
 }
 
-#[derive(Debug)]
-struct ParseEntryError {
-    msg: String,
-}
-
-impl ParseEntryError {
-    pub fn new(msg: String) -> ParseEntryError {
-        ParseEntryError { msg }
-    }
-}
-fn parse_segments(input: &str) -> Vec<Segments> {
-    input.split_whitespace().map(Segments::from).collect()
+/// Parse the groups of segments in `input`, which is a part of `line`
+fn parse_segments(line: &str, input: &str) -> Result<Vec<Segments>, ParseError> {
+    input
+        .split_whitespace()
+        .map(
+            |token| match token.chars().find(|&c| Segment::try_from(c).is_err()) {
+                Some(c) => Err(ParseError::at(
+                    line,
+                    token,
+                    format!("invalid segment '{}' in '{}'", c, token),
+                )),
+                None => Ok(Segments::from(token)),
+            },
+        )
+        .collect()
 }
 
 impl FromStr for Entry {
-    type Err = ParseEntryError;
+    type Err = ParseError;
 
     /// It is assumed that s is a line in the form "s s s s | dd ddddd ddddd dddd" where
     /// s are groups of (input) segments and d are groups of segments that represent digits
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut tokens = s.splitn(2, " | ");
 
-        let patterns = tokens.next().ok_or(ParseEntryError::new(format!(
-            "Missing pattern in line {}",
-            s
-        )))?;
+        let patterns = tokens
+            .next()
+            .ok_or_else(|| ParseError::new(1, "Missing pattern"))?;
 
-        let digits = tokens.next().ok_or(ParseEntryError::new(format!(
-            "Missing digits in line {}",
-            s
-        )))?;
+        let digits = tokens
+            .next()
+            .ok_or_else(|| ParseError::new(s.len() + 1, "Missing digits"))?;
 
-        Ok(Entry(parse_segments(patterns), parse_segments(digits)))
+        Ok(Entry(
+            parse_segments(s, patterns)?,
+            parse_segments(s, digits)?,
+        ))
     }
 }
 
@@ -200,33 +200,38 @@ fn concatenate_integers(input: &[i32]) -> i32 {
     input.iter().fold(0, |a, e| a * 10 + e)
 }
 
-fn part1() {
-    let s = std::fs::read_to_string("days/day8/input.txt").unwrap();
-
-    println!(
-        "Day 8 part 1 : Unique digits {}",
-        count_unique(&s.split_terminator("\n").collect::<Vec<_>>())
-    );
+struct SevenSegmentSearch {
+    entries: Vec<String>,
 }
 
-fn part2() {
-    let reader =
-        BufReader::new(File::open("days/day8/input.txt").expect("Failed to open input file"));
+impl Solution for SevenSegmentSearch {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(SevenSegmentSearch {
+            entries: parse_lines(input, |line| {
+                Entry::from_str(line)?;
+                Ok(line.to_string())
+            })?,
+        })
+    }
 
-    let s = reader
-        .lines()
-        .filter_map(|line| brute_force(&line.expect("OMG")))
-        .map(|r| concatenate_integers(&r))
-        .sum::<i32>();
+    fn part1(&self) -> Answer {
+        count_unique(&self.entries.iter().map(|s| s.as_str()).collect::<Vec<_>>()).into()
+    }
 
-    assert_eq!(s, 973292);
+    fn part2(&self) -> Answer {
+        let s = self
+            .entries
+            .iter()
+            .filter_map(|line| brute_force(line))
+            .map(|r| concatenate_integers(&r))
+            .sum::<i32>();
 
-    println!("Day 8 part 2 : Sum of output values: {}", s);
+        s.into()
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<SevenSegmentSearch>(8, "Seven Segment Search", env!("CARGO_MANIFEST_DIR"));
 }
 
 #[cfg(test)]
@@ -262,9 +267,8 @@ mod tests {
                 .chars()
                 .map(|c| {
                     if p.contains_key(&c) {
-                        p.get(&c)
-                            .expect(format!("Invalid mapping {} in {:?}", c, p).as_str())
-                            .clone()
+                        *p.get(&c)
+                            .unwrap_or_else(|| panic!("Invalid mapping {} in {:?}", c, p))
                     } else {
                         c
                     }
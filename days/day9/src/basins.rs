@@ -0,0 +1,140 @@
+use std::fmt;
+
+use common::{search::flood_fill, Grid, Neighborhood, Point2};
+
+/// Which neighbours a basin spreads to, 9s never belong to a basin.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BasinRule {
+    /// Every location that is not a 9 is in the basin of the locations next to it.
+    Connected,
+    /// A basin only spreads from its low point to higher locations, where smoke would flow down from.
+    Ascending,
+}
+
+/// Every location labelled with the basin it belongs to. Basins are numbered from 0 in the order of the
+/// low points they grew from.
+pub struct Basins {
+    labels: Grid<Option<usize>>,
+    sizes: Vec<usize>,
+}
+
+impl Basins {
+    /// Label the `heights` by growing a basin from every one of the `low_regions` following `rule`, a low
+    /// region being a single low point or a plateau of them. A location goes to the first basin that
+    /// reaches it, so every location is looked at once. Connected locations no low region reached, like
+    /// the far side of a ridge with `BasinRule::Ascending`, form a basin of their own, so every location
+    /// but the 9s is labelled.
+    pub fn new(
+        heights: &Grid<u32>,
+        low_regions: impl IntoIterator<Item = Vec<Point2<i64>>>,
+        rule: BasinRule,
+    ) -> Basins {
+        let mut labels = Grid::from_fn(heights.width(), heights.height(), |_| None);
+        let mut sizes = vec![];
+
+        let leftovers = heights
+            .iter()
+            .map(|(position, _)| (vec![position], BasinRule::Connected));
+
+        for (region, rule) in low_regions
+            .into_iter()
+            .map(|region| (region, rule))
+            .chain(leftovers)
+        {
+            let start = region[0];
+            if heights[&start] == 9 || labels[&start].is_some() {
+                continue;
+            }
+
+            // The whole region belongs to the basin, its locations are not higher than each other
+            let basin_label = Some(sizes.len());
+            for position in &region {
+                labels[position] = basin_label;
+            }
+
+            let basin = flood_fill(start, |position| {
+                let height = heights[position];
+
+                heights
+                    .neighbors(position, Neighborhood::VON_NEUMANN)
+                    .filter(|&(neighbor, &h)| {
+                        h < 9
+                            && (labels[&neighbor] == basin_label
+                                || labels[&neighbor].is_none()
+                                    && (rule == BasinRule::Connected || h > height))
+                    })
+                    .map(|(neighbor, _)| neighbor)
+                    .collect::<Vec<_>>()
+            });
+
+            for position in &basin {
+                labels[position] = basin_label;
+            }
+            sizes.push(basin.len());
+        }
+
+        Basins { labels, sizes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+
+    /// The basin `position` belongs to, `None` for 9s and locations outside the map.
+    pub fn label(&self, position: &Point2<i64>) -> Option<usize> {
+        self.labels.get(position).copied().flatten()
+    }
+
+    /// Number of locations of every basin, indexed by basin.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// The locations of `basin`, row by row.
+    pub fn members(&self, basin: usize) -> impl Iterator<Item = Point2<i64>> + '_ {
+        self.labels
+            .iter()
+            .filter(move |(_, label)| **label == Some(basin))
+            .map(|(position, _)| position)
+    }
+
+    /// Locations where basins meet: a 9 next to two basins or more, or a location next to a basin other
+    /// than its own. Row by row.
+    pub fn boundaries(&self) -> impl Iterator<Item = Point2<i64>> + '_ {
+        self.labels.iter().filter_map(move |(position, &label)| {
+            let mut basins = self
+                .labels
+                .neighbors(&position, Neighborhood::VON_NEUMANN)
+                .filter_map(|(_, &neighbor)| neighbor)
+                .chain(label);
+            let first = basins.next()?;
+
+            basins.any(|basin| basin != first).then_some(position)
+        })
+    }
+
+    /// Product of the sizes of the `n` largest basins.
+    pub fn largest_product(&self, n: usize) -> usize {
+        let mut sizes = self.sizes.clone();
+
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes.iter().take(n).product()
+    }
+}
+
+/// The map with every basin drawn in a letter or digit of its own, cycling after 62 basins, and 9s as '.'.
+impl fmt::Display for Basins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const SYMBOLS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+        let symbols = self.labels.map(|label| match label {
+            Some(basin) => SYMBOLS[basin % SYMBOLS.len()] as char,
+            None => '.',
+        });
+        write!(f, "{}", symbols)
+    }
+}
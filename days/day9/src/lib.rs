@@ -1,9 +1,11 @@
-use std::collections::HashMap;
+mod basins;
+mod terrain;
 
-use common::Coordinate;
-use itertools::Itertools;
+pub use basins::{BasinRule, Basins};
+use common::{Answer, Grid, Neighborhood, ParseError, Point2, Registry, Solution};
+pub use terrain::{Region, Ties};
 
-struct LowPoint {
+pub struct LowPoint {
     height: u32,
 }
 
@@ -13,129 +15,90 @@ impl LowPoint {
     }
 }
 
-struct HeightMap {
-    locations: HashMap<Coordinate<i32>, u32>,
+pub struct HeightMap {
+    locations: Grid<u32>,
 }
 
 impl HeightMap {
-    pub fn parse(input: &[&str]) -> HeightMap {
-        HeightMap {
-            locations: input
-                .iter()
-                .enumerate()
-                .flat_map(|(y, line)| {
-                    line.chars().enumerate().map(move |(x, c)| {
-                        (
-                            Coordinate::new(x as i32, y as i32, 0),
-                            c.to_digit(10).expect("Input is not a digit"),
-                        )
-                    })
-                })
-                .collect(),
-        }
+    pub fn parse(input: &[&str]) -> Result<HeightMap, ParseError> {
+        Ok(HeightMap {
+            locations: Grid::parse(input, |c| {
+                c.to_digit(10)
+                    .ok_or_else(|| format!("Input '{}' is not a digit", c))
+            })?,
+        })
     }
 
-    pub fn neighbors(&self, p: &Coordinate<i32>) -> Vec<(Coordinate<i32>, u32)> {
+    pub fn neighbors<'a>(
+        &'a self,
+        p: &Point2<i64>,
+    ) -> impl Iterator<Item = (Point2<i64>, u32)> + 'a {
         self.locations
-            .iter()
-            .filter_map(|(kn, kv)| {
-                if p.manhattan(kn) == 1 {
-                    Some((*kn, *kv))
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>()
+            .neighbors(p, Neighborhood::VON_NEUMANN)
+            .map(|(neighbor, height)| (neighbor, *height))
     }
 
-    pub fn low_points(&self) -> Vec<(Coordinate<i32>, LowPoint)> {
+    pub fn low_points(&self) -> Vec<(Point2<i64>, LowPoint)> {
         self.locations
             .iter()
             .filter(|(kc, vc)| {
                 // Filter locations where all neighbors have greater 'heights'
-                self.neighbors(kc).iter().all(|(_, vn)| vc < &vn)
+                self.neighbors(kc).all(|(_, vn)| **vc < vn)
             })
-            .map(|(location, height)| (*location, LowPoint { height: *height }))
+            .map(|(location, height)| (location, LowPoint { height: *height }))
             .collect::<Vec<_>>()
     }
 
-    pub fn determine_basins(
-        &self,
-        low_points: &[(Coordinate<i32>, LowPoint)],
-    ) -> Vec<HashMap<Coordinate<i32>, u32>> {
-        let mut basins = vec![];
-
-        for low_point in low_points.iter() {
-            let mut basin = HashMap::from([(low_point.0, low_point.1.height)]);
-
-            self.drill(&mut basin, (low_point.0, low_point.1.height));
-
-            basins.push(basin);
-        }
-        basins
-    }
-
-    fn drill(&self, basin: &mut HashMap<Coordinate<i32>, u32>, current: (Coordinate<i32>, u32)) {
-        let neighbors = self.neighbors(&current.0);
-
-        for neighbor in neighbors {
-            if neighbor.1 < 9 && neighbor.1 > current.1 {
-                if let std::collections::hash_map::Entry::Vacant(e) = basin.entry(neighbor.0) {
-                    e.insert(neighbor.1);
-
-                    //  Do the same for neighbor..
-                    self.drill(basin, neighbor);
-                }
-            }
-        }
-    }
-
-    pub fn basin_size(&self, basins: &[HashMap<Coordinate<i32>, u32>]) -> usize {
-        basins
-            .iter()
-            .sorted_by_key(|basin| basin.len())
-            .rev()
-            .take(3)
-            .fold(1usize, |acc, basin| acc * basin.len())
+    /// Every location labelled with its basin, basins grow from the low regions found with `ties`
+    /// following `rule`.
+    pub fn basins(&self, rule: BasinRule, ties: Ties) -> Basins {
+        Basins::new(
+            &self.locations,
+            self.low_regions(ties)
+                .into_iter()
+                .map(|region| region.locations),
+            rule,
+        )
     }
 }
 
-fn part1() {
-    let s = std::fs::read_to_string("days/day9/input.txt").unwrap();
-
-    let map = HeightMap::parse(s.split_terminator('\n').collect::<Vec<_>>().as_slice());
-
-    let low_points = map.low_points();
-
-    let risk_level = low_points.iter().fold(0, |a, e| a + e.1.risk_level());
-
-    println!("Day 9 part 1 : Risk level {}", risk_level);
+struct SmokeBasin {
+    map: HeightMap,
 }
 
-fn part2() {
-    let s = std::fs::read_to_string("days/day9/input.txt").unwrap();
-
-    let map = HeightMap::parse(s.split_terminator('\n').collect::<Vec<_>>().as_slice());
-
-    let low_points = map.low_points();
-
-    let basins = map.determine_basins(&low_points);
+impl Solution for SmokeBasin {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(SmokeBasin {
+            map: HeightMap::parse(input.lines().collect::<Vec<_>>().as_slice())?,
+        })
+    }
 
-    let sizes = map.basin_size(&basins);
+    fn part1(&self) -> Answer {
+        let low_points = self.map.low_points();
 
-    assert_eq!(sizes, 1047744);
+        low_points
+            .iter()
+            .fold(0, |a, e| a + e.1.risk_level())
+            .into()
+    }
 
-    println!("Day 9 part 2 : Basin sizes multiplied {}", sizes);
+    fn part2(&self) -> Answer {
+        self.map
+            .basins(BasinRule::Connected, Ties::Strict)
+            .largest_product(3)
+            .into()
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+pub fn register(registry: &mut Registry) {
+    registry.register::<SmokeBasin>(9, "Smoke Basin", env!("CARGO_MANIFEST_DIR"));
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::HeightMap;
+    use common::Point2;
+
+    use crate::{BasinRule, HeightMap, Region, Ties};
 
     static TEST_INPUT: &[&str] = &[
         "2199943210",
@@ -147,7 +110,7 @@ mod tests {
 
     #[test]
     fn part1_test1() {
-        let map = HeightMap::parse(TEST_INPUT);
+        let map = HeightMap::parse(TEST_INPUT).unwrap();
 
         let low_points = map.low_points();
 
@@ -160,20 +123,89 @@ mod tests {
 
     #[test]
     fn part2_test1() {
-        let map = HeightMap::parse(TEST_INPUT);
+        let map = HeightMap::parse(TEST_INPUT).unwrap();
 
-        let low_points = map.low_points();
+        for rule in [BasinRule::Ascending, BasinRule::Connected] {
+            let basins = map.basins(rule, Ties::Strict);
 
-        let basins = map.determine_basins(&low_points);
+            assert_eq!(basins.len(), 4);
+            assert_eq!(basins.sizes(), &[3, 9, 14, 9]);
+            assert_eq!(basins.largest_product(3), 1134);
+        }
 
-        assert_eq!(basins.len(), 4);
+        let basins = map.basins(BasinRule::Connected, Ties::Strict);
+        assert_eq!(basins.label(&Point2::new(0, 0)), Some(0));
+        assert_eq!(basins.label(&Point2::new(2, 0)), None);
         assert_eq!(
-            basins.iter().fold(0, |acc, basin| acc + basin.len()),
-            3 + 9 + 14 + 9
+            basins.members(0).collect::<Vec<_>>(),
+            vec![Point2::new(0, 0), Point2::new(1, 0), Point2::new(0, 1)]
+        );
+        assert_eq!(
+            basins.to_string(),
+            [
+                "aa...bbbbb",
+                "a.ccc.b.bb",
+                ".ccccc.d.b",
+                "ccccc.ddd.",
+                ".c...ddddd",
+                "",
+            ]
+            .join("\n")
         );
 
-        let sizes = map.basin_size(&basins);
+        // Ascending from the 1 stops at the 3, the 2 behind it is a low point of its own
+        let map = HeightMap::parse(&["1323"]).unwrap();
+        assert_eq!(
+            map.basins(BasinRule::Ascending, Ties::Strict).sizes(),
+            &[2, 2]
+        );
+        assert_eq!(map.basins(BasinRule::Connected, Ties::Strict).sizes(), &[4]);
+
+        // Ascending from the 1 stops at the second 2, the rest is not reached uphill and forms a basin of
+        // its own
+        let map = HeightMap::parse(&["12234"]).unwrap();
+        let basins = map.basins(BasinRule::Ascending, Ties::Strict);
+        assert_eq!(basins.sizes(), &[2, 3]);
+        assert!((0..5).all(|x| basins.label(&Point2::new(x, 0)).is_some()));
+    }
+
+    #[test]
+    fn terrain_test1() {
+        let map = HeightMap::parse(&["9999999", "9112349", "9999999"]).unwrap();
+
+        // The two 1s are each other's neighbour, so neither is strictly lower, without low points the
+        // whole valley is one leftover basin
+        assert!(map.low_points().is_empty());
+        assert_eq!(map.basins(BasinRule::Ascending, Ties::Strict).sizes(), &[5]);
+
+        let plateau = Region {
+            height: 1,
+            locations: vec![Point2::new(1, 1), Point2::new(2, 1)],
+        };
+        assert_eq!(map.low_regions(Ties::Plateaus), vec![plateau.clone()]);
+        assert_eq!(map.plateaus().len(), 2);
+        assert!(map.plateaus().contains(&plateau));
+        assert_eq!(
+            map.basins(BasinRule::Ascending, Ties::Plateaus).sizes(),
+            &[5]
+        );
+
+        let map = HeightMap::parse(&["959", "131", "959"]).unwrap();
+        assert_eq!(map.saddle_points(), vec![Point2::new(1, 1)]);
+        assert_eq!(map.low_regions(Ties::Plateaus).len(), 2);
+
+        let map = HeightMap::parse(TEST_INPUT).unwrap();
+        assert_eq!(
+            map.low_regions(Ties::Plateaus),
+            map.low_regions(Ties::Strict)
+        );
 
-        assert_eq!(sizes, 1134);
+        let boundaries = map
+            .basins(BasinRule::Connected, Ties::Strict)
+            .boundaries()
+            .collect::<Vec<_>>();
+        assert!(boundaries.contains(&Point2::new(2, 0)));
+        assert!(!boundaries.contains(&Point2::new(0, 0)));
+        assert!(!boundaries.contains(&Point2::new(9, 4)));
     }
 }
@@ -0,0 +1,107 @@
+use common::{search::connected_components, Point2};
+
+use crate::HeightMap;
+
+/// Whether locations of equal height next to each other can together be the lowest around.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Ties {
+    /// Only a location with all its neighbours strictly higher is low, like the puzzle asks.
+    Strict,
+    /// A connected area of equal height is low as a whole when all locations around it are higher.
+    Plateaus,
+}
+
+/// Connected locations of one height.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Region {
+    pub height: u32,
+    /// Row by row.
+    pub locations: Vec<Point2<i64>>,
+}
+
+impl Region {
+    pub fn risk_level(&self) -> u32 {
+        self.height + 1
+    }
+}
+
+impl HeightMap {
+    /// Every connected area of equal height, single locations included, in the order they are first
+    /// found row by row.
+    fn regions(&self) -> Vec<Region> {
+        let locations = &self.locations;
+
+        connected_components(locations.iter().map(|(position, _)| position), |position| {
+            let height = locations[position];
+
+            locations
+                .neighbors(position, common::Neighborhood::VON_NEUMANN)
+                .filter(move |&(_, &h)| h == height)
+                .map(|(neighbor, _)| neighbor)
+        })
+        .into_iter()
+        .map(|mut component| {
+            component.sort_by_key(|p| (p.y, p.x));
+
+            Region {
+                height: locations[&component[0]],
+                locations: component,
+            }
+        })
+        .collect()
+    }
+
+    /// The areas of two locations or more that share one height.
+    pub fn plateaus(&self) -> Vec<Region> {
+        self.regions()
+            .into_iter()
+            .filter(|region| region.locations.len() > 1)
+            .collect()
+    }
+
+    /// The low points, or low plateaus too depending on `ties`.
+    pub fn low_regions(&self, ties: Ties) -> Vec<Region> {
+        match ties {
+            Ties::Strict => self
+                .low_points()
+                .into_iter()
+                .map(|(location, low_point)| Region {
+                    height: low_point.height,
+                    locations: vec![location],
+                })
+                .collect(),
+            Ties::Plateaus => self
+                .regions()
+                .into_iter()
+                .filter(|region| {
+                    region.height < 9
+                        && region.locations.iter().all(|location| {
+                            self.neighbors(location)
+                                .all(|(_, height)| height >= region.height)
+                        })
+                })
+                .collect(),
+        }
+    }
+
+    /// Locations higher than the neighbours on one axis and lower than the ones on the other, the pass
+    /// between two hills and two valleys. Only locations with all four neighbours qualify.
+    pub fn saddle_points(&self) -> Vec<Point2<i64>> {
+        let locations = &self.locations;
+
+        locations
+            .iter()
+            .filter(|&(position, &height)| {
+                let around = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                    .map(|(x, y)| locations.get(&(position + Point2::new(x, y))).copied());
+                let [Some(left), Some(right), Some(up), Some(down)] = around else {
+                    return false;
+                };
+
+                (left < height && right < height && up > height && down > height)
+                    || (left > height && right > height && up < height && down < height)
+            })
+            .map(|(position, _)| position)
+            .collect()
+    }
+}
@@ -1,20 +1,43 @@
-fn part1() {
-    println!("Day 16 part 1 : ");
+use common::{parse_lines, Answer, ParseError, Registry, Solution};
+
+struct Puzzle {
+    lines: Vec<String>,
 }
 
-fn part2() {
-    println!("Day 16 part 2 : ");
+impl Solution for Puzzle {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Puzzle {
+            lines: parse_lines(input, |line| Ok(line.to_string()))?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        self.lines.len().into()
+    }
+
+    fn part2(&self) -> Answer {
+        "".into()
+    }
 }
 
-pub fn run() {
-    part1();
-    part2();
+/// Day 0 is never run by main, a copy gets the day number and title of its puzzle and is added to the
+/// registry there.
+pub fn register(registry: &mut Registry) {
+    registry.register::<Puzzle>(0, "Template", env!("CARGO_MANIFEST_DIR"));
 }
 
 #[cfg(test)]
 mod tests {
+    use common::{Answer, Solution};
+
+    use crate::Puzzle;
+
+    static TEST_INPUT: &str = "first line\nsecond line";
+
     #[test]
     fn part1_test1() {
-        assert!(false);
+        let puzzle = Puzzle::parse(TEST_INPUT).unwrap();
+
+        assert_eq!(puzzle.part1(), Answer::Number(2));
     }
 }
@@ -0,0 +1,172 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use common::{Day, ParseError};
+
+/// How to print benchmark results.
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    /// Aligned table for reading
+    Text,
+    /// One row per day and step
+    Csv,
+    /// An array with one object per day and step
+    Json,
+}
+
+/// Summary of the repeated runs of one step.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Stats {
+        assert!(!samples.is_empty(), "need at least one sample");
+
+        samples.sort();
+
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+
+        Stats {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Timing of one step, parse, part1 or part2, of a day.
+pub struct Timing {
+    pub day: u32,
+    pub step: &'static str,
+    pub stats: Stats,
+}
+
+/// Call `f` `warmup` times without timing it, then `runs` times with, which has to be at least once.
+fn measure<T>(warmup: usize, runs: usize, mut f: impl FnMut() -> T) -> Stats {
+    for _ in 0..warmup {
+        black_box(f());
+    }
+
+    let samples = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            let result = black_box(f());
+            let elapsed = start.elapsed();
+            // Dropping what was built, like a parsed puzzle, is not part of the time
+            drop(result);
+            elapsed
+        })
+        .collect();
+
+    Stats::from_samples(samples)
+}
+
+/// Time parsing `input` and both parts of `day` separately.
+pub fn bench_day(
+    day: &Day,
+    input: &str,
+    warmup: usize,
+    runs: usize,
+) -> Result<Vec<Timing>, ParseError> {
+    let solution = day.parse(input)?;
+
+    let timing = |step, stats| Timing {
+        day: day.day,
+        step,
+        stats,
+    };
+
+    Ok(vec![
+        timing("parse", measure(warmup, runs, || day.parse(input))),
+        timing("part1", measure(warmup, runs, || solution.part1())),
+        timing("part2", measure(warmup, runs, || solution.part2())),
+    ])
+}
+
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1e6
+}
+
+pub fn print(timings: &[Timing], format: Format) {
+    match format {
+        Format::Text => {
+            println!(
+                "{:>3} {:<5} {:>12} {:>12} {:>12}",
+                "day", "step", "min", "median", "max"
+            );
+            for t in timings {
+                println!(
+                    "{:>3} {:<5} {:>12?} {:>12?} {:>12?}",
+                    t.day, t.step, t.stats.min, t.stats.median, t.stats.max
+                );
+            }
+        }
+        Format::Csv => {
+            println!("day,step,min_us,median_us,max_us");
+            for t in timings {
+                println!(
+                    "{},{},{:.3},{:.3},{:.3}",
+                    t.day,
+                    t.step,
+                    micros(t.stats.min),
+                    micros(t.stats.median),
+                    micros(t.stats.max)
+                );
+            }
+        }
+        Format::Json => {
+            let rows = timings
+                .iter()
+                .map(|t| {
+                    format!(
+                        "  {{\"day\": {}, \"step\": \"{}\", \"min_us\": {:.3}, \"median_us\": {:.3}, \"max_us\": {:.3}}}",
+                        t.day,
+                        t.step,
+                        micros(t.stats.min),
+                        micros(t.stats.median),
+                        micros(t.stats.max)
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            println!("[\n{}\n]", rows.join(",\n"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Stats;
+
+    #[test]
+    fn stats_test1() {
+        let ms = Duration::from_millis;
+
+        assert_eq!(
+            Stats::from_samples(vec![ms(3), ms(1), ms(2)]),
+            Stats {
+                min: ms(1),
+                median: ms(2),
+                max: ms(3)
+            }
+        );
+        assert_eq!(
+            Stats::from_samples(vec![ms(4), ms(1), ms(2), ms(8)]).median,
+            ms(3)
+        );
+    }
+}
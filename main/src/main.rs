@@ -1,18 +1,508 @@
-fn main() {
-    day1::run();
-    day2::run();
-    day3::run();
-    day4::run();
-    day5::run();
-    day6::run();
-    day7::run();
-    day8::run();
-    day9::run();
-    day10::run();
-    day11::run();
-    day12::run();
-    day13::run();
-    day14::run();
-    day15::run();
-    day16::run();
+mod bench;
+
+use std::{path::PathBuf, process::ExitCode, time::Duration};
+
+use clap::{Parser, Subcommand};
+use common::{
+    animation::Animation, answers, input, Answer, Answers, Day, ParseError, Registry, Solution,
+    Verdict,
+};
+
+#[derive(Parser)]
+#[command(about = "Advent of Code 2021 solutions")]
+struct Cli {
+    /// What to do, runs all days when omitted
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a single day
+    Run {
+        /// Day to run
+        #[arg(short, long)]
+        day: u32,
+
+        /// Only run this part
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+
+        /// Puzzle input to use instead of the day's own, see also ADVENT_INPUT_DIR
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// List all registered days
+    List,
+    /// Run all registered days
+    All,
+    /// Check answers against the known ones in each day's answers.toml
+    Verify {
+        /// Only verify this day
+        #[arg(short, long)]
+        day: Option<u32>,
+    },
+    /// Time parsing and both parts of each day
+    Bench {
+        /// Only benchmark this day
+        #[arg(short, long)]
+        day: Option<u32>,
+
+        /// Untimed runs before measuring
+        #[arg(short, long, default_value_t = 1)]
+        warmup: usize,
+
+        /// Timed runs of every step
+        #[arg(short, long, default_value_t = 10, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        runs: usize,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = bench::Format::Text)]
+        format: bench::Format,
+    },
+    /// Print the day 12 cave system as Graphviz DOT, render it with `dot -Tsvg`
+    Caves {
+        /// Puzzle input to use instead of the day's own, see also ADVENT_INPUT_DIR
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// Highlight the n-th path from start to end, counting from 1
+        #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        path: Option<usize>,
+
+        /// Find the path following the rules of this part
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+    },
+    /// Show the day 15 route with the lowest risk, on the terminal or as a PPM image
+    Route {
+        /// Puzzle input to use instead of the day's own, see also ADVENT_INPUT_DIR
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// Repeat the map this many times in both directions, 5 for part 2
+        #[arg(short, long, default_value_t = 1, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        tiles: usize,
+
+        /// Write a PPM image to this file instead of printing the map
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Pixels per position in the image
+        #[arg(short, long, default_value_t = 4, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        scale: usize,
+    },
+    /// Animate a grid on the terminal: day 5 vent lines, day 11 octopus flashes or day 13 folds
+    Animate {
+        /// Day to animate, 5, 11 or 13
+        #[arg(short, long)]
+        day: u32,
+
+        /// Puzzle input to use instead of the day's own, see also ADVENT_INPUT_DIR
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// Show what this part needs: diagonal vents, 100 steps or until all flash, the first or all folds
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        /// Milliseconds between frames
+        #[arg(long, default_value_t = 200)]
+        delay: u64,
+
+        /// Start paused, showing the next frame on every Enter
+        #[arg(long)]
+        paused: bool,
+    },
+}
+
+fn registry() -> Registry {
+    let mut registry = Registry::default();
+
+    day1::register(&mut registry);
+    day2::register(&mut registry);
+    day3::register(&mut registry);
+    day4::register(&mut registry);
+    day5::register(&mut registry);
+    day6::register(&mut registry);
+    day7::register(&mut registry);
+    day8::register(&mut registry);
+    day9::register(&mut registry);
+    day10::register(&mut registry);
+    day11::register(&mut registry);
+    day12::register(&mut registry);
+    day13::register(&mut registry);
+    day14::register(&mut registry);
+    day15::register(&mut registry);
+    day16::register(&mut registry);
+
+    registry
+}
+
+/// Render `error` the way a compiler would, quoting the offending line of `input`.
+fn diagnostic(error: &ParseError, input: &str) -> String {
+    let mut result = error.to_string();
+
+    if let Some(line) = input.lines().nth(error.line.wrapping_sub(1)) {
+        let gutter = error.line.to_string().len();
+
+        result += &format!(
+            "\n{:gutter$} |\n{} | {}\n{:gutter$} | {:>column$}",
+            "",
+            error.line,
+            line,
+            "",
+            "^",
+            gutter = gutter,
+            column = error.column.max(1)
+        );
+    }
+    result
+}
+
+/// Multi-line answers, like letters drawn on a grid, start on a line of their own.
+fn display(answer: &Answer) -> String {
+    match answer {
+        Answer::Text(s) if s.contains('\n') => format!("\n{}", s),
+        _ => answer.to_string(),
+    }
+}
+
+fn load(day: &Day, input: Option<&PathBuf>) -> Result<(PathBuf, Box<dyn Solution>), String> {
+    let path = day.input_path(input.map(|p| p.as_path()));
+
+    let text = input::read(&path).map_err(|e| e.to_string())?;
+
+    let solution = day
+        .parse(&text)
+        .map_err(|e| diagnostic(&e.in_file(&path), &text))?;
+
+    Ok((path, solution))
+}
+
+fn run_day(day: &Day, part: Option<u8>, input: Option<&PathBuf>) -> Result<(), String> {
+    let (_, solution) = load(day, input)?;
+
+    if part.is_none() || part == Some(1) {
+        println!("Day {} part 1 : {}", day.day, display(&solution.part1()));
+    }
+    if part.is_none() || part == Some(2) {
+        println!("Day {} part 2 : {}", day.day, display(&solution.part2()));
+    }
+    Ok(())
+}
+
+/// Run both parts of `day` and compare them with the recorded answers, returns whether nothing failed.
+fn verify_day(day: &Day) -> bool {
+    let (path, solution) = match load(day, None) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            println!("Day {} FAIL : {}", day.day, e);
+            return false;
+        }
+    };
+
+    let answers = match Answers::load(&answers::answers_path(&path)) {
+        Ok(answers) => answers,
+        Err(e) => {
+            println!("Day {} FAIL : {}", day.day, e);
+            return false;
+        }
+    };
+
+    let mut passed = true;
+
+    for (part, answer) in [(1, solution.part1()), (2, solution.part2())] {
+        match answers.check(part, &answer) {
+            Verdict::Pass => println!("Day {} part {} : pass", day.day, part),
+            Verdict::Unknown => println!(
+                "Day {} part {} : unknown, got {}",
+                day.day,
+                part,
+                display(&answer)
+            ),
+            Verdict::Fail { expected } => {
+                println!(
+                    "Day {} part {} : FAIL, expected {} got {}",
+                    day.day,
+                    part,
+                    display(&expected),
+                    display(&answer)
+                );
+                passed = false;
+            }
+        }
+    }
+    passed
+}
+
+fn bench_day(day: &Day, warmup: usize, runs: usize) -> Result<Vec<bench::Timing>, String> {
+    let path = day.input_path(None);
+
+    let text = input::read(&path).map_err(|e| e.to_string())?;
+
+    bench::bench_day(day, &text, warmup, runs).map_err(|e| diagnostic(&e.in_file(&path), &text))
+}
+
+fn bench_days(
+    days: &[&Day],
+    warmup: usize,
+    runs: usize,
+    format: bench::Format,
+) -> Result<(), String> {
+    let mut timings = vec![];
+
+    for day in days {
+        timings.extend(bench_day(day, warmup, runs)?);
+    }
+    bench::print(&timings, format);
+    Ok(())
+}
+
+/// The day 12 caves of `input` in DOT, with the `n`-th path allowed in `part` highlighted.
+fn caves_dot(
+    day: &Day,
+    input: Option<&PathBuf>,
+    part: u8,
+    n: Option<usize>,
+) -> Result<String, String> {
+    let path = day.input_path(input.map(|p| p.as_path()));
+
+    let text = input::read(&path).map_err(|e| e.to_string())?;
+
+    let caves = day12::CaveGraph::parse(&text).map_err(|e| diagnostic(&e.in_file(&path), &text))?;
+
+    let policy = match part {
+        1 => day12::VisitPolicy::default(),
+        _ => day12::VisitPolicy::default().revisits(1),
+    };
+
+    let selected = match n {
+        Some(n) => Some(
+            caves
+                .paths(&policy)
+                .nth(n - 1)
+                .ok_or_else(|| format!("there are fewer than {} paths", n))?,
+        ),
+        None => None,
+    };
+
+    Ok(caves.to_dot(selected.as_deref()))
+}
+
+/// Print the safest day 15 route through the `input` map tiled `tiles` times, or write it as an image
+/// to `output`.
+fn show_route(
+    day: &Day,
+    input: Option<&PathBuf>,
+    tiles: usize,
+    output: Option<&PathBuf>,
+    scale: usize,
+) -> Result<(), String> {
+    let path = day.input_path(input.map(|p| p.as_path()));
+
+    let text = input::read(&path).map_err(|e| e.to_string())?;
+
+    let map = day15::RiskMap::parse(&text.lines().collect::<Vec<_>>())
+        .map_err(|e| diagnostic(&e.in_file(&path), &text))?
+        .tiled(tiles, tiles);
+
+    let route = day15::safest_route(&map).ok_or("the map is empty")?;
+
+    match output {
+        Some(output) => {
+            std::fs::write(output, day15::ppm(&map, &route, scale))
+                .map_err(|e| format!("{}: {}", output.display(), e))?;
+            println!("Risk {}, route written to {}", route.risk, output.display());
+        }
+        None => println!("{}Risk {}", day15::ansi(&map, &route), route.risk),
+    }
+    Ok(())
+}
+
+/// Play the frames of day 5, 11 or 13 for `part` of the `input` on the terminal.
+fn animate(
+    day: &Day,
+    input: Option<&PathBuf>,
+    part: u8,
+    delay: Duration,
+    paused: bool,
+) -> Result<(), String> {
+    let path = day.input_path(input.map(|p| p.as_path()));
+
+    let text = input::read(&path).map_err(|e| e.to_string())?;
+
+    let parse_error = |e: ParseError| diagnostic(&e.in_file(&path), &text);
+
+    let mut animation = Animation::terminal().delay(delay).paused(paused);
+
+    match day.day {
+        5 => {
+            let vents = day5::HydrothermalVenture::parse(&text).map_err(parse_error)?;
+
+            for (n, counts) in vents.accumulation(part == 2).enumerate() {
+                let overlaps = counts.values().filter(|&&count| count >= 2).count();
+                let caption = format!("Line {}: {} overlaps", n + 1, overlaps);
+
+                if !animation
+                    .frame(&caption, &counts, day5::vent_cell)
+                    .map_err(|e| e.to_string())?
+                {
+                    break;
+                }
+            }
+        }
+        11 => {
+            let mut simulation = day11::FlashSimulation::parse(&text).map_err(parse_error)?;
+
+            // Octopuses that never flash together still end once their states repeat
+            let last = match part {
+                1 => 100,
+                _ => simulation.first_synchronised().unwrap_or_else(|| {
+                    let cycle = simulation.cycle();
+                    cycle.start + cycle.length
+                }),
+            };
+
+            let mut total = 0;
+            loop {
+                let stats = simulation.step();
+                total += stats.flashes;
+                let caption = format!(
+                    "Step {}: {} flashes, {} in total",
+                    stats.step, stats.flashes, total
+                );
+
+                if !animation
+                    .frame(&caption, simulation.energy(), day11::octopus_cell)
+                    .map_err(|e| e.to_string())?
+                    || stats.step == last
+                {
+                    break;
+                }
+            }
+        }
+        13 => {
+            let origami = day13::TransparentOrigami::parse(&text).map_err(parse_error)?;
+            let papers = if part == 1 { 2 } else { usize::MAX };
+
+            for (n, paper) in origami.papers().take(papers).enumerate() {
+                let dots = paper.values().filter(|&&dot| dot).count();
+                let caption = format!("Fold {}: {} dots", n, dots);
+
+                if !animation
+                    .frame(&caption, &paper, day13::dot_cell)
+                    .map_err(|e| e.to_string())?
+                {
+                    break;
+                }
+            }
+        }
+        n => return Err(format!("day {} has no animation, try 5, 11 or 13", n)),
+    }
+    Ok(())
+}
+
+/// The days a command applies to, either the one asked for or all of them. Reports an unknown day.
+fn select(registry: &Registry, day: Option<u32>) -> Option<Vec<&Day>> {
+    match day {
+        Some(n) => match registry.get(n) {
+            Some(day) => Some(vec![day]),
+            None => {
+                eprintln!("Day {} is not registered, see `list`", n);
+                None
+            }
+        },
+        None => Some(registry.iter().collect()),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let registry = registry();
+
+    let result = match cli.command.unwrap_or(Command::All) {
+        Command::Run { day, part, input } => match registry.get(day) {
+            Some(day) => run_day(day, part, input.as_ref()),
+            None => {
+                eprintln!("Day {} is not registered, see `list`", day);
+                return ExitCode::from(2);
+            }
+        },
+        Command::List => {
+            for day in registry.iter() {
+                println!("{:>2} {}", day.day, day.title);
+            }
+            Ok(())
+        }
+        Command::All => registry.iter().try_for_each(|day| run_day(day, None, None)),
+        Command::Verify { day } => {
+            let Some(days) = select(&registry, day) else {
+                return ExitCode::from(2);
+            };
+
+            // Verify every day, even after a failure, so one run reports all regressions
+            let failed = days.into_iter().filter(|day| !verify_day(day)).count();
+
+            if failed == 0 {
+                Ok(())
+            } else {
+                Err(format!("{} day(s) failed verification", failed))
+            }
+        }
+        Command::Bench {
+            day,
+            warmup,
+            runs,
+            format,
+        } => {
+            let Some(days) = select(&registry, day) else {
+                return ExitCode::from(2);
+            };
+
+            bench_days(&days, warmup, runs, format)
+        }
+        Command::Caves { input, path, part } => {
+            let day = registry.get(12).expect("day 12 is registered");
+
+            caves_dot(day, input.as_ref(), part, path).map(|dot| print!("{}", dot))
+        }
+        Command::Route {
+            input,
+            tiles,
+            output,
+            scale,
+        } => {
+            let day = registry.get(15).expect("day 15 is registered");
+
+            show_route(day, input.as_ref(), tiles, output.as_ref(), scale)
+        }
+        Command::Animate {
+            day,
+            input,
+            part,
+            delay,
+            paused,
+        } => match registry.get(day) {
+            Some(day) => animate(
+                day,
+                input.as_ref(),
+                part,
+                Duration::from_millis(delay),
+                paused,
+            ),
+            None => {
+                eprintln!("Day {} is not registered, see `list`", day);
+                return ExitCode::from(2);
+            }
+        },
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
 }